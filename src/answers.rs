@@ -0,0 +1,163 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+
+use winnow::prelude::*;
+
+use crate::challenge::{registry, Answer, Solution};
+use crate::error::Error;
+use crate::select::Challenge;
+
+/// Accepted answers recorded locally, one `<year>:<day>p<part>: <answer>` entry per line. Entries
+/// without a year are from before years were recorded, and so from 2024.
+#[derive(Debug, Clone)]
+pub struct KnownAnswers {
+    path: PathBuf,
+    answers: BTreeMap<Challenge, Answer>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Verdict {
+    Correct,
+    Wrong(Answer),
+    Unknown,
+}
+
+impl KnownAnswers {
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
+        let path = path.as_ref().to_path_buf();
+        let text = match fs::read_to_string(&path) {
+            Ok(text) => text,
+            Err(err) if err.kind() == ErrorKind::NotFound => String::new(),
+            Err(err) => return Err(err.into()),
+        };
+
+        let mut answers = BTreeMap::new();
+        for (n, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let (challenge, answer) = Self::parse_entry.parse(line).map_err(|_| {
+                Error::KnownAnswersError(format!("{}:{}: invalid entry", path.display(), n + 1))
+            })?;
+            answers.insert(challenge, answer);
+        }
+
+        Ok(Self { path, answers })
+    }
+
+    fn parse_entry(input: &mut &str) -> PResult<(Challenge, Answer)> {
+        use winnow::ascii::space0;
+        use winnow::combinator::{rest, separated_pair};
+
+        separated_pair(
+            Challenge::parse_in(registry::DEFAULT_YEAR),
+            (space0, ':', space0),
+            rest.verify(|answer: &str| !answer.trim().is_empty())
+                .try_map(str::parse::<Answer>),
+        )
+        .parse_next(input)
+    }
+
+    /// Write an entry as [`KnownAnswers::parse_entry`] reads it back
+    fn format_entry(challenge: &Challenge, answer: &Answer) -> String {
+        format!("{}: {}", challenge.to_entry(), answer)
+    }
+
+    pub fn save(&self) -> Result<(), Error> {
+        let mut text = String::from("# accepted answers, see `aoc24 accept`\n");
+        for (challenge, answer) in &self.answers {
+            text.push_str(&Self::format_entry(challenge, answer));
+            text.push('\n');
+        }
+
+        fs::write(&self.path, text)?;
+        Ok(())
+    }
+
+    pub fn get(&self, challenge: &Challenge) -> Option<&Answer> {
+        self.answers.get(challenge)
+    }
+
+    pub fn insert(&mut self, challenge: Challenge, answer: Answer) -> Option<Answer> {
+        self.answers.insert(challenge, answer)
+    }
+
+    pub fn verdict(&self, challenge: &Challenge, answer: &Answer) -> Verdict {
+        match self.get(challenge) {
+            Some(known) if known == answer => Verdict::Correct,
+            Some(known) => Verdict::Wrong(known.clone()),
+            None => Verdict::Unknown,
+        }
+    }
+
+    /// Mark each successful solution as correct, wrong or unknown
+    pub fn verify(&self, solutions: &mut [Solution]) {
+        for solution in solutions {
+            if let Ok(answer) = &solution.solution {
+                solution.verdict = Some(self.verdict(&solution.challenge, answer));
+            }
+        }
+    }
+}
+
+impl fmt::Display for Verdict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Correct => write!(f, "correct"),
+            Self::Wrong(expected) => write!(f, "wrong, expected {}", expected),
+            Self::Unknown => write!(f, "unknown"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::select::ChallengePart;
+
+    #[test]
+    fn test_parse_entry() {
+        let (challenge, answer) = KnownAnswers::parse_entry.parse("12p2: 4815162342").unwrap();
+        assert_eq!(challenge, Challenge::new(2024, 12, ChallengePart::Second));
+        assert_eq!(answer, 4815162342u64.into());
+
+        let (challenge, _) = KnownAnswers::parse_entry.parse("2023:5p1: 35").unwrap();
+        assert_eq!(challenge, Challenge::new(2023, 5, ChallengePart::First));
+
+        let entry = KnownAnswers::format_entry(&challenge, &35.into());
+        assert_eq!(entry, "2023:5p1: 35");
+        assert_eq!(
+            KnownAnswers::parse_entry.parse(&entry).unwrap(),
+            (challenge, 35.into())
+        );
+
+        let (_, answer) = KnownAnswers::parse_entry.parse("3p1:EHZFRUHK").unwrap();
+        assert_eq!(answer, "EHZFRUHK".into());
+
+        assert!(KnownAnswers::parse_entry.parse("3p3: 1").is_err());
+        assert!(KnownAnswers::parse_entry.parse("3p1: ").is_err());
+    }
+
+    #[test]
+    fn test_verdict() {
+        let mut known = KnownAnswers {
+            path: PathBuf::new(),
+            answers: BTreeMap::new(),
+        };
+        let challenge = Challenge::new(2024, 1, ChallengePart::First);
+
+        assert_eq!(known.verdict(&challenge, &11.into()), Verdict::Unknown);
+
+        known.insert(challenge, "11".parse().unwrap());
+        assert_eq!(known.verdict(&challenge, &11usize.into()), Verdict::Correct);
+        assert_eq!(
+            known.verdict(&challenge, &12.into()),
+            Verdict::Wrong(11.into())
+        );
+    }
+}
@@ -0,0 +1,83 @@
+use chrono::{DateTime, Datelike, FixedOffset, NaiveDate, TimeZone, Utc};
+
+/// The first Advent of Code
+pub const FIRST_YEAR: usize = 2015;
+pub const FIRST_DAY: usize = 1;
+pub const LAST_DAY: usize = 25;
+
+/// Puzzles unlock at midnight UTC-5, the timezone the Advent of Code calendar runs on
+fn unlock_timezone() -> FixedOffset {
+    FixedOffset::west_opt(5 * 3600).expect("valid offset")
+}
+
+/// When the puzzle for `day` of `year` unlocks, or `None` if there is no such puzzle
+pub fn unlock_time(year: usize, day: usize) -> Option<DateTime<Utc>> {
+    if !(FIRST_DAY..=LAST_DAY).contains(&day) {
+        return None;
+    }
+
+    let midnight = NaiveDate::from_ymd_opt(year as i32, 12, day as u32)?.and_hms_opt(0, 0, 0)?;
+    let unlock = unlock_timezone().from_local_datetime(&midnight).single()?;
+
+    Some(unlock.with_timezone(&Utc))
+}
+
+pub fn is_unlocked(year: usize, day: usize, now: DateTime<Utc>) -> bool {
+    unlock_time(year, day).is_some_and(|unlock| unlock <= now)
+}
+
+/// The year of the most recent Advent of Code to have started
+pub fn current_year(now: DateTime<Utc>) -> usize {
+    let year = now.with_timezone(&unlock_timezone()).year() as usize;
+
+    if is_unlocked(year, FIRST_DAY, now) {
+        year
+    } else {
+        year - 1
+    }
+}
+
+/// The year and day of the most recently unlocked puzzle, while an Advent of Code is running
+pub fn advent_day(now: DateTime<Utc>) -> Option<(usize, usize)> {
+    let now = now.with_timezone(&unlock_timezone());
+    let day = now.day() as usize;
+
+    if now.month() == 12 && (FIRST_DAY..=LAST_DAY).contains(&day) {
+        Some((now.year() as usize, day))
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn utc(s: &str) -> DateTime<Utc> {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn test_unlock() {
+        assert_eq!(unlock_time(2024, 1), Some(utc("2024-12-01T05:00:00Z")));
+        assert_eq!(unlock_time(2024, 26), None);
+
+        assert!(!is_unlocked(2024, 6, utc("2024-12-06T04:59:59Z")));
+        assert!(is_unlocked(2024, 6, utc("2024-12-06T05:00:00Z")));
+    }
+
+    #[test]
+    fn test_current_year() {
+        assert_eq!(current_year(utc("2024-12-01T05:00:00Z")), 2024);
+        assert_eq!(current_year(utc("2024-12-01T04:59:59Z")), 2023);
+        assert_eq!(current_year(utc("2025-03-14T00:00:00Z")), 2024);
+    }
+
+    #[test]
+    fn test_advent_day() {
+        assert_eq!(advent_day(utc("2024-12-06T04:59:59Z")), Some((2024, 5)));
+        assert_eq!(advent_day(utc("2024-12-06T05:00:00Z")), Some((2024, 6)));
+        assert_eq!(advent_day(utc("2024-12-01T04:00:00Z")), None);
+        assert_eq!(advent_day(utc("2024-12-26T05:00:00Z")), None);
+    }
+}
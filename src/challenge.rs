@@ -1,12 +1,17 @@
+mod answer;
+pub mod bench;
+pub mod cancel;
 mod day1;
 mod day2;
 mod day3;
 mod day4;
 mod day5;
+mod day6;
 mod error;
+pub mod registry;
 mod solver;
-mod day6;
 
+pub use answer::*;
 pub use error::*;
 pub use solver::*;
 
@@ -0,0 +1,165 @@
+use std::convert::Infallible;
+use std::fmt;
+use std::str::FromStr;
+
+use num_bigint::BigInt;
+use serde::{Serialize, Serializer};
+
+/// The answer to a challenge part.
+///
+/// Integer answers compare by value regardless of which variant holds them, so an answer parsed
+/// from text matches the same number produced by a solver.
+#[derive(Debug, Clone)]
+pub enum Answer {
+    Signed(i64),
+    Unsigned(u64),
+    Signed128(i128),
+    Unsigned128(u128),
+    Big(BigInt),
+    Text(String),
+}
+
+impl Answer {
+    fn to_bigint(&self) -> Option<BigInt> {
+        match self {
+            Self::Signed(n) => Some(BigInt::from(*n)),
+            Self::Unsigned(n) => Some(BigInt::from(*n)),
+            Self::Signed128(n) => Some(BigInt::from(*n)),
+            Self::Unsigned128(n) => Some(BigInt::from(*n)),
+            Self::Big(n) => Some(n.clone()),
+            Self::Text(_) => None,
+        }
+    }
+}
+
+impl PartialEq for Answer {
+    fn eq(&self, other: &Self) -> bool {
+        use Answer::*;
+
+        match (self, other) {
+            (Signed(a), Signed(b)) => a == b,
+            (Unsigned(a), Unsigned(b)) => a == b,
+            (Text(a), Text(b)) => a == b,
+            (Text(_), _) | (_, Text(_)) => false,
+            _ => self.to_bigint() == other.to_bigint(),
+        }
+    }
+}
+
+impl Eq for Answer {}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Signed(n) => write!(f, "{}", n),
+            Self::Unsigned(n) => write!(f, "{}", n),
+            Self::Signed128(n) => write!(f, "{}", n),
+            Self::Unsigned128(n) => write!(f, "{}", n),
+            Self::Big(n) => write!(f, "{}", n),
+            Self::Text(s) => write!(f, "{}", s),
+        }
+    }
+}
+
+impl FromStr for Answer {
+    type Err = Infallible;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+
+        let answer = if let Ok(n) = s.parse::<u64>() {
+            Self::Unsigned(n)
+        } else if let Ok(n) = s.parse::<i64>() {
+            Self::Signed(n)
+        } else if let Ok(n) = s.parse::<u128>() {
+            Self::Unsigned128(n)
+        } else if let Ok(n) = s.parse::<i128>() {
+            Self::Signed128(n)
+        } else if let Ok(n) = s.parse::<BigInt>() {
+            Self::Big(n)
+        } else {
+            Self::Text(s.to_string())
+        };
+
+        Ok(answer)
+    }
+}
+
+impl Serialize for Answer {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Self::Signed(n) => serializer.serialize_i64(*n),
+            Self::Unsigned(n) => serializer.serialize_u64(*n),
+            Self::Signed128(n) => serializer.serialize_i128(*n),
+            Self::Unsigned128(n) => serializer.serialize_u128(*n),
+            Self::Big(n) => serializer.collect_str(n),
+            Self::Text(s) => serializer.serialize_str(s),
+        }
+    }
+}
+
+macro_rules! answer_from {
+    ($variant:ident as $target:ty: $($source:ty),+) => {
+        $(
+            impl From<$source> for Answer {
+                fn from(value: $source) -> Self {
+                    Self::$variant(value as $target)
+                }
+            }
+        )+
+    };
+}
+
+answer_from!(Signed as i64: i8, i16, i32, i64, isize);
+answer_from!(Unsigned as u64: u8, u16, u32, u64, usize);
+answer_from!(Signed128 as i128: i128);
+answer_from!(Unsigned128 as u128: u128);
+
+impl From<BigInt> for Answer {
+    fn from(value: BigInt) -> Self {
+        Self::Big(value)
+    }
+}
+
+impl From<String> for Answer {
+    fn from(value: String) -> Self {
+        Self::Text(value)
+    }
+}
+
+impl From<&str> for Answer {
+    fn from(value: &str) -> Self {
+        Self::Text(value.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compare() {
+        assert_eq!(Answer::from(42usize), Answer::from(42i32));
+        assert_eq!(Answer::from(42u128), Answer::from(42i64));
+        assert_eq!(Answer::from(BigInt::from(-7)), Answer::from(-7i8));
+        assert_ne!(Answer::from(42usize), Answer::from("42"));
+        assert_ne!(Answer::from(-1i64), Answer::from(u64::MAX));
+    }
+
+    #[test]
+    fn test_parse() {
+        let parse = |s: &str| s.parse::<Answer>().unwrap();
+
+        assert!(matches!(parse("123"), Answer::Unsigned(123)));
+        assert!(matches!(parse(" -5\n"), Answer::Signed(-5)));
+        assert!(matches!(
+            parse("340282366920938463463374607431768211455"),
+            Answer::Unsigned128(u128::MAX)
+        ));
+        assert!(matches!(
+            parse("999999999999999999999999999999999999999999"),
+            Answer::Big(_)
+        ));
+        assert!(matches!(parse("LGYEUOI"), Answer::Text(_)));
+    }
+}
@@ -0,0 +1,290 @@
+use std::fmt::{Display, Formatter};
+use std::sync::Arc;
+use std::time::Duration;
+
+use itertools::Itertools;
+use tokio::sync::mpsc;
+use tokio::task::spawn_blocking;
+use tokio::time::timeout_at;
+
+use crate::challenge::cancel::Cancellation;
+use crate::challenge::*;
+use crate::error::thread_panic_string;
+use crate::input::Input;
+use crate::select::Challenge;
+
+#[derive(Debug, Copy, Clone)]
+pub struct BenchOptions {
+    pub warmup: usize,
+    pub runs: usize,
+}
+
+#[derive(Debug, Default, Copy, Clone)]
+pub struct Stats {
+    pub min: Duration,
+    pub median: Duration,
+    pub mean: Duration,
+    pub stddev: Duration,
+}
+
+impl Stats {
+    pub fn from_samples(samples: &[Duration]) -> Self {
+        if samples.is_empty() {
+            return Self::default();
+        }
+
+        let sorted = samples.iter().copied().sorted().collect_vec();
+        let mid = sorted.len() / 2;
+        let median = if sorted.len() % 2 == 0 {
+            (sorted[mid - 1] + sorted[mid]) / 2
+        } else {
+            sorted[mid]
+        };
+
+        let mean = sorted.iter().map(Duration::as_secs_f64).sum::<f64>() / sorted.len() as f64;
+        let variance = if sorted.len() > 1 {
+            sorted
+                .iter()
+                .map(|sample| (sample.as_secs_f64() - mean).powi(2))
+                .sum::<f64>()
+                / (sorted.len() - 1) as f64
+        } else {
+            0.0
+        };
+
+        Self {
+            min: sorted[0],
+            median,
+            mean: Duration::from_secs_f64(mean),
+            stddev: Duration::from_secs_f64(variance.sqrt()),
+        }
+    }
+}
+
+impl Display for Stats {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "min {:.2?}, median {:.2?}, mean {:.2?}, stddev {:.2?}",
+            self.min, self.median, self.mean, self.stddev
+        )
+    }
+}
+
+#[derive(Debug)]
+pub struct Benchmark {
+    pub challenge: Challenge,
+    pub result: Result<BenchResult>,
+}
+
+#[derive(Debug)]
+pub struct BenchResult {
+    pub answer: Answer,
+    pub parse: Stats,
+    pub solve: Stats,
+}
+
+impl Display for Benchmark {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match &self.result {
+            Ok(result) => write!(
+                f,
+                "{}: {}\n\t\tparse: {}\n\t\tsolve: {}",
+                self.challenge, result.answer, result.parse, result.solve
+            ),
+            Err(err) => write!(f, "{}: ERROR {}", self.challenge, err),
+        }
+    }
+}
+
+/// Combined timings over every successful benchmark in a selection
+#[derive(Debug, Default)]
+pub struct BenchSummary {
+    pub benchmarks: usize,
+    pub failures: usize,
+    pub parse_mean: Duration,
+    pub solve_mean: Duration,
+    pub parse_median: Duration,
+    pub solve_median: Duration,
+}
+
+impl BenchSummary {
+    pub fn new(benchmarks: &[Benchmark]) -> Self {
+        benchmarks
+            .iter()
+            .fold(Self::default(), |mut summary, benchmark| {
+                summary.benchmarks += 1;
+                match &benchmark.result {
+                    Ok(result) => {
+                        summary.parse_mean += result.parse.mean;
+                        summary.solve_mean += result.solve.mean;
+                        summary.parse_median += result.parse.median;
+                        summary.solve_median += result.solve.median;
+                    },
+                    Err(_) => summary.failures += 1,
+                }
+
+                summary
+            })
+    }
+}
+
+impl Display for BenchSummary {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Total ({} challenges, {} failed): mean {:.2?} (parse {:.2?}, solve {:.2?}), median {:.2?} (parse {:.2?}, solve {:.2?})",
+            self.benchmarks,
+            self.failures,
+            self.parse_mean + self.solve_mean,
+            self.parse_mean,
+            self.solve_mean,
+            self.parse_median + self.solve_median,
+            self.parse_median,
+            self.solve_median,
+        )
+    }
+}
+
+/// Benchmark each challenge in turn, so runs don't compete with each other for the CPU
+pub async fn bench_all(
+    challenges: Vec<Challenge>,
+    input: Arc<dyn Input>,
+    options: BenchOptions,
+    timeouts: Timeouts,
+) -> Vec<Benchmark> {
+    let deadline = timeouts
+        .total
+        .map(|total| tokio::time::Instant::now() + total);
+
+    let mut benchmarks = Vec::new();
+    for challenge in challenges.into_iter().sorted().dedup() {
+        benchmarks.push(
+            bench(
+                challenge,
+                input.clone(),
+                options,
+                timeouts.challenge,
+                deadline,
+            )
+            .await,
+        );
+    }
+
+    benchmarks
+}
+
+/// Benchmark a challenge on a blocking thread, giving each run up to `timeout` to finish and
+/// cancelling the solver when that, or the overall `deadline`, passes
+pub async fn bench(
+    challenge: Challenge,
+    input: Arc<dyn Input>,
+    options: BenchOptions,
+    timeout: Option<Duration>,
+    deadline: Option<tokio::time::Instant>,
+) -> Benchmark {
+    let Some(solver) = get_challenge(challenge.year, challenge.day, input) else {
+        return Benchmark {
+            challenge,
+            result: Err(missing_solver(challenge.year, challenge.day)),
+        };
+    };
+
+    if deadline.is_some_and(|deadline| deadline <= tokio::time::Instant::now()) {
+        return Benchmark {
+            challenge,
+            result: Err(Error::CancelledError()),
+        };
+    }
+
+    let total_runs = options.warmup + options.runs;
+    let cancellation = Cancellation::new();
+    let (sender, mut receiver) = mpsc::unbounded_channel();
+    let handle = spawn_blocking({
+        let cancellation = cancellation.clone();
+        move || {
+            cancellation.scope(|| {
+                for _ in 0..total_runs {
+                    let solution = solver.solve_one(challenge);
+                    let failed = solution.solution.is_err();
+                    if sender.send(solution).is_err() || failed {
+                        break;
+                    }
+                }
+            })
+        }
+    });
+
+    let mut parse = Vec::with_capacity(options.runs);
+    let mut solve = Vec::with_capacity(options.runs);
+    let mut answer = None;
+    for run in 0..total_runs {
+        let started = tokio::time::Instant::now();
+        let limit = [timeout.map(|timeout| started + timeout), deadline]
+            .into_iter()
+            .flatten()
+            .min();
+
+        let received = match limit {
+            Some(limit) => timeout_at(limit, receiver.recv()).await,
+            None => Ok(receiver.recv().await),
+        };
+
+        let err = match received {
+            Ok(Some(solution)) => match solution.solution {
+                Ok(run_answer) => {
+                    if run >= options.warmup {
+                        parse.push(solution.timings.parse);
+                        solve.push(solution.timings.solve);
+                        answer = Some(run_answer);
+                    }
+                    continue;
+                },
+                Err(err) => err,
+            },
+            Ok(None) => {
+                let message = match handle.await {
+                    Ok(()) => "no solution produced".to_string(),
+                    Err(err) => thread_panic_string(err),
+                };
+                Error::SolverPanicError(message)
+            },
+            Err(_) => {
+                // The solver keeps its thread until it notices the cancellation
+                cancellation.cancel();
+                Error::TimeoutError(started.elapsed())
+            },
+        };
+
+        return Benchmark {
+            challenge,
+            result: Err(err),
+        };
+    }
+
+    let result = answer
+        .ok_or(Error::NoSolutionError())
+        .map(|answer| BenchResult {
+            answer,
+            parse: Stats::from_samples(&parse),
+            solve: Stats::from_samples(&solve),
+        });
+
+    Benchmark { challenge, result }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stats() {
+        let samples = [4, 1, 3, 2].map(Duration::from_millis);
+        let stats = Stats::from_samples(&samples);
+
+        assert_eq!(stats.min, Duration::from_millis(1));
+        assert_eq!(stats.median, Duration::from_micros(2500));
+        assert_eq!(stats.mean, Duration::from_micros(2500));
+        assert_eq!(stats.stddev.as_micros(), 1290);
+    }
+}
@@ -0,0 +1,76 @@
+use std::cell::RefCell;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use crate::challenge::{Error, Result};
+
+thread_local! {
+    static CURRENT: RefCell<Option<Cancellation>> = const { RefCell::new(None) };
+}
+
+/// Cooperative cancellation for a solver running on a blocking thread.
+///
+/// Solvers can't be stopped from the outside, so long loops should call [`checkpoint`] now and
+/// then, which fails once the challenge they are working on has timed out.
+#[derive(Debug, Clone, Default)]
+pub struct Cancellation {
+    cancelled: Arc<AtomicBool>,
+}
+
+impl Cancellation {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+
+    /// Run `f` with this as the current thread's cancellation
+    pub fn scope<T>(&self, f: impl FnOnce() -> T) -> T {
+        let previous = CURRENT.with(|current| current.replace(Some(self.clone())));
+        let result = f();
+        CURRENT.with(|current| current.replace(previous));
+
+        result
+    }
+}
+
+/// Fails with [`Error::CancelledError`] once the running challenge has been cancelled
+pub fn checkpoint() -> Result<()> {
+    let cancelled = CURRENT.with(|current| {
+        current
+            .borrow()
+            .as_ref()
+            .is_some_and(Cancellation::is_cancelled)
+    });
+
+    if cancelled {
+        Err(Error::CancelledError())
+    } else {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_checkpoint() {
+        let cancellation = Cancellation::new();
+        assert!(checkpoint().is_ok());
+
+        cancellation.scope(|| {
+            assert!(checkpoint().is_ok());
+            cancellation.cancel();
+            assert!(matches!(checkpoint(), Err(Error::CancelledError())));
+        });
+
+        assert!(checkpoint().is_ok());
+    }
+}
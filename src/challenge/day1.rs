@@ -1,23 +1,23 @@
 use std::collections::HashMap;
 use std::fmt::Debug;
-use std::sync::{Arc, Mutex};
 
 use winnow::prelude::*;
 
-use crate::challenge::{Error, Result, Solver};
+use crate::challenge::registry::register_solver;
+use crate::challenge::{Answer, Error, Result, Solver};
 use crate::input::helpers::{parse_lines, InputHelper};
-use crate::input::Input;
 
 #[derive(Debug)]
 pub struct Day1 {
-    input: Arc<Mutex<dyn Input>>,
+    input: InputHelper,
 }
 
+register_solver!(Day1, day = 1, title = "Historian Hysteria");
+
 impl Day1 {
     fn read_lists(&self) -> Result<(Vec<usize>, Vec<usize>)> {
-        let helper = InputHelper::new(1, self.input.clone());
         let lists = self
-            .read_numbers(&mut helper.all_text()?.as_str())
+            .read_numbers(&mut &*self.input.all_text()?)
             .map_err(|err| Error::LineParseError(err.to_string()))?
             .into_iter()
             .unzip();
@@ -47,44 +47,48 @@ impl Day1 {
 }
 
 impl Solver for Day1 {
-    fn new(input: Arc<Mutex<dyn Input>>) -> Self {
+    type Parsed = (Vec<usize>, Vec<usize>);
+
+    fn new(input: InputHelper) -> Self {
         Self { input }
     }
 
-    fn solve_part_1(&self) -> Result<String> {
-        let (mut left, mut right) = self.read_lists()?;
+    fn parse(&self) -> Result<Self::Parsed> {
+        self.read_lists()
+    }
 
+    fn solve_part_1(&self, (left, right): &mut Self::Parsed) -> Result<Answer> {
         left.sort();
         right.sort();
 
         let total_difference = left
-            .into_iter()
-            .zip(right.into_iter())
-            .map(|(left, right)| left.abs_diff(right))
+            .iter()
+            .zip(right.iter())
+            .map(|(left, right)| left.abs_diff(*right))
             .sum::<usize>();
 
-        Ok(format!("{total_difference}"))
+        Ok(total_difference.into())
     }
 
-    fn solve_part_2(&self) -> Result<String> {
-        let (left, right) = self.read_lists()?;
-
+    fn solve_part_2(&self, (left, right): &mut Self::Parsed) -> Result<Answer> {
         let mut counts = HashMap::new();
-        for number in right {
-            counts.entry(number).and_modify(|n| *n += 1).or_insert(1);
+        for number in right.iter() {
+            counts.entry(*number).and_modify(|n| *n += 1).or_insert(1);
         }
 
         let similarity_score = left
-            .into_iter()
-            .map(|n| counts.get(&n).map(|c| n * c).unwrap_or(0))
+            .iter()
+            .map(|n| counts.get(n).map(|c| n * c).unwrap_or(0))
             .sum::<usize>();
 
-        Ok(format!("{similarity_score}"))
+        Ok(similarity_score.into())
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use std::sync::Arc;
+
     use super::*;
     use crate::input::helpers::test_input;
 
@@ -100,8 +104,9 @@ mod tests {
         "#;
 
         let input = test_input(input);
-        let solver = Day1::new(Arc::new(Mutex::new(input)));
-        assert_eq!(solver.solve_part_1().unwrap(), "11");
-        assert_eq!(solver.solve_part_2().unwrap(), "31");
+        let solver = Day1::new(InputHelper::new(2024, 1, Arc::new(input)));
+        let mut parsed = solver.parse().unwrap();
+        assert_eq!(solver.solve_part_1(&mut parsed).unwrap(), 11.into());
+        assert_eq!(solver.solve_part_2(&mut parsed).unwrap(), 31.into());
     }
 }
@@ -1,11 +1,9 @@
 use itertools::Itertools;
-use std::sync::Arc;
-use std::sync::Mutex;
 use winnow::prelude::*;
 
-use crate::challenge::{Error, Result, Solver};
-use crate::input::helpers::{parse_lines, InputHelper};
-use crate::input::Input;
+use crate::challenge::registry::register_solver;
+use crate::challenge::{Answer, Error, Result, Solver};
+use crate::input::helpers::InputHelper;
 
 #[derive(Debug, Copy, Clone)]
 struct Record(usize, i64);
@@ -47,29 +45,39 @@ impl Derivative {
 
 #[derive(Debug)]
 pub struct Day2 {
-    input: Arc<Mutex<dyn Input>>,
+    input: InputHelper,
 }
 
+register_solver!(Day2, day = 2, title = "Red-Nosed Reports");
+
 impl Day2 {
     fn read_reports(&self) -> Result<Vec<Vec<i64>>> {
-        let helper = InputHelper::new(2, self.input.clone());
-        let reports = self
-            .read_numbers(&mut helper.all_text()?.as_str())
-            .map_err(|err| Error::LineParseError(err.to_string()))?;
+        // Each report is on its own line, so parse them as they're read
+        let mut reports = Vec::new();
+        for line in self.input.lines()? {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            let report = Self::read_numbers(&mut line.as_str())
+                .map_err(|err| Error::LineParseError(err.to_string()))?;
+            reports.push(report);
+        }
 
         Ok(reports)
     }
 
-    fn read_numbers<'a>(&self, input: &'a mut &'a str) -> PResult<Vec<Vec<i64>>> {
+    fn read_numbers(input: &mut &str) -> PResult<Vec<i64>> {
         use winnow::ascii::{digit1, space1};
         use winnow::combinator::separated;
 
-        let parser = separated(
+        separated(
             1..,
             digit1.try_map(|num_str: &str| num_str.parse::<i64>()),
             space1,
-        );
-        parse_lines(input, parser)
+        )
+        .parse_next(input)
     }
 
     fn report_is_safe(report: &Vec<i64>) -> bool {
@@ -144,33 +152,40 @@ impl Day2 {
 }
 
 impl Solver for Day2 {
-    fn new(input: Arc<Mutex<dyn Input>>) -> Self {
+    type Parsed = Vec<Vec<i64>>;
+
+    fn new(input: InputHelper) -> Self {
         Self { input }
     }
 
-    fn solve_part_1(&self) -> Result<String> {
-        let safe_reports = self
-            .read_reports()?
-            .into_iter()
-            .filter(Self::report_is_safe)
+    fn parse(&self) -> Result<Self::Parsed> {
+        self.read_reports()
+    }
+
+    fn solve_part_1(&self, reports: &mut Self::Parsed) -> Result<Answer> {
+        let safe_reports = reports
+            .iter()
+            .filter(|report| Self::report_is_safe(report))
             .count();
 
-        Ok(safe_reports.to_string())
+        Ok(safe_reports.into())
     }
 
-    fn solve_part_2(&self) -> Result<String> {
-        let mended_reports = self
-            .read_reports()?
-            .into_iter()
+    fn solve_part_2(&self, reports: &mut Self::Parsed) -> Result<Answer> {
+        let mended_reports = reports
+            .iter()
+            .cloned()
             .filter_map(Self::mend_report)
             .collect_vec();
 
-        Ok(mended_reports.len().to_string())
+        Ok(mended_reports.len().into())
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use std::sync::Arc;
+
     use super::*;
     use crate::input::helpers::test_input;
 
@@ -186,9 +201,10 @@ mod tests {
         "#;
 
         let input = test_input(input);
-        let solver = Day2::new(Arc::new(Mutex::new(input)));
-        assert_eq!(solver.solve_part_1().unwrap(), "2");
-        assert_eq!(solver.solve_part_2().unwrap(), "4");
+        let solver = Day2::new(InputHelper::new(2024, 2, Arc::new(input)));
+        let mut parsed = solver.parse().unwrap();
+        assert_eq!(solver.solve_part_1(&mut parsed).unwrap(), 2.into());
+        assert_eq!(solver.solve_part_2(&mut parsed).unwrap(), 4.into());
     }
 
     #[test]
@@ -198,8 +214,9 @@ mod tests {
         "#;
 
         let input = test_input(input);
-        let solver = Day2::new(Arc::new(Mutex::new(input)));
-        assert_eq!(solver.solve_part_2().unwrap(), "0");
+        let solver = Day2::new(InputHelper::new(2024, 2, Arc::new(input)));
+        let mut parsed = solver.parse().unwrap();
+        assert_eq!(solver.solve_part_2(&mut parsed).unwrap(), 0.into());
     }
 
     #[test]
@@ -215,7 +232,8 @@ mod tests {
         let expected = input.lines().filter(|l| !l.trim().is_empty()).count();
 
         let input = test_input(input);
-        let solver = Day2::new(Arc::new(Mutex::new(input)));
-        assert_eq!(solver.solve_part_2().unwrap(), expected.to_string());
+        let solver = Day2::new(InputHelper::new(2024, 2, Arc::new(input)));
+        let mut parsed = solver.parse().unwrap();
+        assert_eq!(solver.solve_part_2(&mut parsed).unwrap(), expected.into());
     }
 }
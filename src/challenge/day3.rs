@@ -1,55 +1,26 @@
-use std::sync::{Arc, Mutex};
 use winnow::prelude::*;
 
-use crate::challenge::{Error, Result, Solver};
+use crate::challenge::registry::register_solver;
+use crate::challenge::{Answer, Error, Result, Solver};
 use crate::input::helpers::InputHelper;
-use crate::input::Input;
 
 #[derive(Debug)]
 pub struct Day3 {
-    input: Arc<Mutex<dyn Input>>,
+    input: InputHelper,
 }
 
+register_solver!(Day3, day = 3, title = "Mull It Over");
+
 #[derive(Debug, Clone)]
-enum Cmd {
+pub enum Cmd {
     None,
     Do(bool),
     Mul(i64, i64),
 }
 
 impl Day3 {
-    fn read_muls(&self) -> Result<Vec<i64>> {
-        let helper = InputHelper::new(3, self.input.clone());
-        let commands = self
-            .read_mul_calls(&mut helper.all_text()?.as_str())
-            .map_err(|err| Error::LineParseError(err.to_string()))?;
-
-        Ok(commands)
-    }
-
-    fn read_mul_calls<'a>(&self, input: &'a mut &'a str) -> PResult<Vec<i64>> {
-        use winnow::ascii::dec_int;
-        use winnow::combinator::{alt, delimited, preceded, repeat, separated_pair};
-        use winnow::token::take_until;
-
-        let mut parser = repeat(
-            0..,
-            preceded(
-                take_until(0.., "mul"),
-                alt((
-                    delimited("mul(", separated_pair(dec_int, ',', dec_int), ')')
-                        .map(|(a, b): (i64, i64)| a * b),
-                    "mul".map(|_| 0),
-                )),
-            ),
-        );
-
-        parser.parse_next(input)
-    }
-
     fn read_commands(&self) -> Result<Vec<Cmd>> {
-        let helper = InputHelper::new(3, self.input.clone());
-        let commands = Self::parse_commands(&mut helper.all_text()?.as_str())
+        let commands = Self::parse_commands(&mut &*self.input.all_text()?)
             .map_err(|err| Error::LineParseError(err.to_string()))?;
 
         Ok(commands)
@@ -88,25 +59,35 @@ impl Day3 {
 }
 
 impl Solver for Day3 {
-    fn new(input: Arc<Mutex<dyn Input>>) -> Self
+    type Parsed = Vec<Cmd>;
+
+    fn new(input: InputHelper) -> Self
     where
         Self: Sized,
     {
         Self { input }
     }
 
-    fn solve_part_1(&self) -> Result<String> {
-        let result = self.read_muls()?.into_iter().sum::<i64>();
-
-        Ok(result.to_string())
+    fn parse(&self) -> Result<Self::Parsed> {
+        self.read_commands()
     }
 
-    fn solve_part_2(&self) -> Result<String> {
-        let commands = self.read_commands()?;
+    fn solve_part_1(&self, commands: &mut Self::Parsed) -> Result<Answer> {
+        let result = commands
+            .iter()
+            .map(|cmd| match cmd {
+                Cmd::Mul(lhs, rhs) => lhs * rhs,
+                _ => 0,
+            })
+            .sum::<i64>();
 
+        Ok(result.into())
+    }
+
+    fn solve_part_2(&self, commands: &mut Self::Parsed) -> Result<Answer> {
         let mut do_flag = true;
         let mut total = 0;
-        for cmd in &commands {
+        for cmd in commands.iter() {
             match cmd {
                 Cmd::Do(flag) => {
                     do_flag = *flag;
@@ -118,12 +99,14 @@ impl Solver for Day3 {
             }
         }
 
-        Ok(total.to_string())
+        Ok(total.into())
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use std::sync::Arc;
+
     use super::*;
     use crate::input::helpers::test_input;
 
@@ -132,8 +115,9 @@ mod tests {
         let input = r#"xmul(2,4)%&mul[3,7]!@^do_not_mul(5,5)+mul(32,64]then(mul(11,8)mul(8,5))"#;
 
         let input = test_input(input);
-        let solver = Day3::new(Arc::new(Mutex::new(input)));
-        assert_eq!(solver.solve_part_1().unwrap(), "161");
+        let solver = Day3::new(InputHelper::new(2024, 3, Arc::new(input)));
+        let mut parsed = solver.parse().unwrap();
+        assert_eq!(solver.solve_part_1(&mut parsed).unwrap(), 161.into());
     }
 
     #[test]
@@ -141,7 +125,8 @@ mod tests {
         let input = r#"xmul(2,4)&mul[3,7]!^don't()_mul(5,5)+mul(32,64](mul(11,8)undo()?mul(8,5))"#;
 
         let input = test_input(input);
-        let solver = Day3::new(Arc::new(Mutex::new(input)));
-        assert_eq!(solver.solve_part_2().unwrap(), "48");
+        let solver = Day3::new(InputHelper::new(2024, 3, Arc::new(input)));
+        let mut parsed = solver.parse().unwrap();
+        assert_eq!(solver.solve_part_2(&mut parsed).unwrap(), 48.into());
     }
 }
\ No newline at end of file
@@ -1,30 +1,34 @@
 use std::collections::HashMap;
 use std::fmt::Display;
-use std::sync::{Arc, Mutex};
 
 use itertools::Itertools;
 
-use crate::challenge::Solver;
+use crate::challenge::registry::register_solver;
+use crate::challenge::{Answer, Solver};
 use crate::input::helpers::InputHelper;
-use crate::input::Input;
 
 #[derive(Debug)]
 pub struct Day4 {
-    input: Arc<Mutex<dyn Input>>,
+    input: InputHelper,
 }
 
+register_solver!(Day4, day = 4, title = "Ceres Search");
+
 impl Solver for Day4 {
-    fn new(input: Arc<Mutex<dyn Input>>) -> Self
+    type Parsed = WordSearch;
+
+    fn new(input: InputHelper) -> Self
     where
         Self: Sized,
     {
         Self { input }
     }
 
-    fn solve_part_1(&self) -> crate::challenge::Result<String> {
-        let helper = InputHelper::new(4, self.input.clone());
-        let search = WordSearch::new(helper.all_text()?);
+    fn parse(&self) -> crate::challenge::Result<Self::Parsed> {
+        Ok(WordSearch::new(&self.input.all_bytes()?))
+    }
 
+    fn solve_part_1(&self, search: &mut Self::Parsed) -> crate::challenge::Result<Answer> {
         let x = search.find_all('X');
         let m = x
             .into_iter()
@@ -39,15 +43,12 @@ impl Solver for Day4 {
             .flat_map(|pos| search.next_position(pos, 'S'))
             .collect_vec();
 
-        Ok(s.len().to_string())
+        Ok(s.len().into())
     }
 
-    fn solve_part_2(&self) -> crate::challenge::Result<String> {
+    fn solve_part_2(&self, search: &mut Self::Parsed) -> crate::challenge::Result<Answer> {
         use Direction::*;
 
-        let helper = InputHelper::new(4, self.input.clone());
-        let search = WordSearch::new(helper.all_text()?);
-
         let m = search.find_all('M');
         let a = m
             .into_iter()
@@ -94,7 +95,7 @@ impl Solver for Day4 {
             })
             .collect_vec();
 
-        Ok((xmases.len() / 2).to_string())
+        Ok((xmases.len() / 2).into())
     }
 }
 
@@ -136,18 +137,21 @@ impl Display for Direction {
     }
 }
 
-struct WordSearch {
+pub struct WordSearch {
     rows: usize,
     cols: usize,
     data: Vec<Vec<char>>,
 }
 
 impl WordSearch {
-    fn new<S: AsRef<str>>(input: S) -> Self {
+    /// The puzzle is all ASCII, so there's no need to check it's UTF-8 first
+    fn new(input: &[u8]) -> Self {
         let data = input
-            .as_ref()
-            .lines()
-            .map(|line| line.chars().collect_vec())
+            .strip_suffix(b"\n")
+            .unwrap_or(input)
+            .split(|&b| b == b'\n')
+            .map(|line| line.strip_suffix(b"\r").unwrap_or(line))
+            .map(|line| line.iter().map(|&b| b as char).collect_vec())
             .collect_vec();
 
         let rows = data.len();
@@ -228,6 +232,8 @@ impl WordSearch {
 
 #[cfg(test)]
 mod tests {
+    use std::sync::Arc;
+
     use super::*;
     use crate::input::helpers::test_input;
 
@@ -248,8 +254,9 @@ mod tests {
         "#,
         );
 
-        let solver = Day4::new(Arc::new(Mutex::new(input)));
-        assert_eq!(solver.solve_part_1().unwrap(), "18");
-        assert_eq!(solver.solve_part_2().unwrap(), "9");
+        let solver = Day4::new(InputHelper::new(2024, 4, Arc::new(input)));
+        let mut parsed = solver.parse().unwrap();
+        assert_eq!(solver.solve_part_1(&mut parsed).unwrap(), 18.into());
+        assert_eq!(solver.solve_part_2(&mut parsed).unwrap(), 9.into());
     }
 }
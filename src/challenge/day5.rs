@@ -1,49 +1,81 @@
 use std::cmp::Ordering;
 use std::collections::{HashMap, HashSet};
-use std::sync::{Arc, Mutex};
 
 use itertools::Itertools;
 use winnow::prelude::*;
 
+use crate::challenge::registry::register_solver;
 use crate::challenge::Solver;
-use crate::challenge::{Error, Result};
+use crate::challenge::{Answer, Error, Result};
 use crate::input::helpers::InputHelper;
-use crate::input::Input;
 
 #[derive(Debug)]
 pub struct Day5 {
-    input: Arc<Mutex<dyn Input>>,
+    input: InputHelper,
 }
 
+register_solver!(Day5, day = 5, title = "Print Queue");
+
 type Rules = HashMap<usize, HashSet<usize>>;
 type Updates = Vec<Vec<usize>>;
 
+#[derive(Debug)]
+pub struct PrintQueue {
+    rules: Rules,
+    updates: Updates,
+    valid: Option<Vec<bool>>,
+}
+
+impl PrintQueue {
+    fn new((rules, updates): (Rules, Updates)) -> Self {
+        Self {
+            rules,
+            updates,
+            valid: None,
+        }
+    }
+
+    /// Check each update against the rules, keeping the result for whichever part runs next
+    fn validate(&mut self) -> &Self {
+        if self.valid.is_none() {
+            let valid = self
+                .updates
+                .iter()
+                .map(|update| Day5::validate_update(update, &self.rules))
+                .collect();
+
+            self.valid = Some(valid);
+        }
+
+        self
+    }
+
+    fn updates(&self, valid: bool) -> impl Iterator<Item = &[usize]> {
+        self.updates
+            .iter()
+            .zip(self.valid.iter().flatten())
+            .filter(move |(_, is_valid)| **is_valid == valid)
+            .map(|(update, _)| update.as_slice())
+    }
+}
+
 impl Day5 {
     fn validate_update(update: &[usize], rules: &Rules) -> bool {
         let mut seen: HashSet<usize> = HashSet::new();
 
-        println!("update: {:?}", update);
         for page in update {
             let Some(page_rules) = rules.get(page) else {
-                println!("\t{}: no rule", page);
                 seen.insert(*page);
                 continue;
             };
 
-            for cannot_precede in page_rules {
-                if seen.contains(cannot_precede) {
-                    println!("\t{}: page {} already seen", page, cannot_precede);
-                    return false;
-                } else {
-                    println!("\t{}: page {} has not been seen", page, cannot_precede);
-                }
+            if page_rules.iter().any(|cannot_precede| seen.contains(cannot_precede)) {
+                return false;
             }
 
-            println!("\t{}: rule {:?} passed", page, page_rules);
             seen.insert(*page);
         }
 
-        println!("\tPASS");
         true
     }
 
@@ -68,9 +100,8 @@ impl Day5 {
     }
 
     fn read_rules_and_updates(&self) -> Result<(Rules, Updates)> {
-        let helper = InputHelper::new(5, self.input.clone());
         let data = self
-            .parse_rules_and_updates(&mut helper.all_text()?.as_str())
+            .parse_rules_and_updates(&mut &*self.input.all_text()?)
             .map_err(|err| Error::LineParseError(err.to_string()))?;
 
         Ok(data)
@@ -92,13 +123,11 @@ impl Day5 {
                     set
                 });
         }
-        dbg!(&rules);
 
         let mut updates = Vec::new();
         for update in updates_lines {
             updates.push(update);
         }
-        dbg!(&updates);
 
         Ok((rules, updates))
     }
@@ -128,41 +157,46 @@ impl Day5 {
 }
 
 impl Solver for Day5 {
-    fn new(input: Arc<Mutex<dyn Input>>) -> Self
+    type Parsed = PrintQueue;
+
+    fn new(input: InputHelper) -> Self
     where
         Self: Sized,
     {
         Self { input }
     }
 
-    fn solve_part_1(&self) -> Result<String> {
-        let (rules, updates) = self.read_rules_and_updates()?;
+    fn parse(&self) -> Result<Self::Parsed> {
+        self.read_rules_and_updates().map(PrintQueue::new)
+    }
 
-        let result = updates
-            .into_iter()
-            .filter(|update| Self::validate_update(update, &rules))
+    fn solve_part_1(&self, queue: &mut Self::Parsed) -> Result<Answer> {
+        let result = queue
+            .validate()
+            .updates(true)
             .map(|pages| pages[pages.len() / 2])
             .sum::<usize>();
 
-        Ok(result.to_string())
+        Ok(result.into())
     }
 
-    fn solve_part_2(&self) -> Result<String> {
-        let (rules, updates) = self.read_rules_and_updates()?;
+    fn solve_part_2(&self, queue: &mut Self::Parsed) -> Result<Answer> {
+        let queue = queue.validate();
 
-        let result = updates
-            .into_iter()
-            .filter(|update| !Self::validate_update(update, &rules))
-            .map(|pages| Self::sort_update(&pages, &rules))
+        let result = queue
+            .updates(false)
+            .map(|pages| Self::sort_update(pages, &queue.rules))
             .map(|pages| pages[pages.len() / 2])
             .sum::<usize>();
 
-        Ok(result.to_string())
+        Ok(result.into())
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use std::sync::Arc;
+
     use super::*;
     use crate::input::helpers::test_input;
 
@@ -201,8 +235,9 @@ mod tests {
         "#,
         );
 
-        let solver = Day5::new(Arc::new(Mutex::new(input)));
-        assert_eq!(solver.solve_part_1().unwrap(), "143");
-        assert_eq!(solver.solve_part_2().unwrap(), "123");
+        let solver = Day5::new(InputHelper::new(2024, 5, Arc::new(input)));
+        let mut parsed = solver.parse().unwrap();
+        assert_eq!(solver.solve_part_1(&mut parsed).unwrap(), 143.into());
+        assert_eq!(solver.solve_part_2(&mut parsed).unwrap(), 123.into());
     }
 }
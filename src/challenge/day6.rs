@@ -1,41 +1,65 @@
-use std::sync::{Arc, Mutex};
+use std::collections::HashSet;
 
-use crate::challenge::{Error, Result, Solver};
+use crate::challenge::cancel::checkpoint;
+use crate::challenge::registry::register_solver;
+use crate::challenge::{Answer, Error, Result, Solver};
 use crate::input::helpers::InputHelper;
-use crate::input::Input;
 
 #[derive(Debug)]
 pub struct Day6 {
-    input: Arc<Mutex<dyn Input>>,
+    input: InputHelper,
 }
 
+register_solver!(Day6, day = 6, title = "Guard Gallivant");
+
 impl Day6 {
     fn parse_map(&self) -> Result<Map> {
-        let helper = InputHelper::new(6, self.input.clone());
-        Map::parse(helper.all_text()?.as_str())
+        Map::parse(&self.input.all_text()?)
     }
 }
 
 impl Solver for Day6 {
-    fn new(input: Arc<Mutex<dyn Input>>) -> Self
+    type Parsed = Map;
+
+    fn new(input: InputHelper) -> Self
     where
         Self: Sized,
     {
         Self { input }
     }
 
-    fn solve_part_1(&self) -> Result<String> {
-        let map = self.parse_map()?;
-        todo!()
+    fn parse(&self) -> Result<Self::Parsed> {
+        self.parse_map()
     }
 
-    fn solve_part_2(&self) -> Result<String> {
-        todo!()
+    fn solve_part_1(&self, map: &mut Self::Parsed) -> Result<Answer> {
+        Ok(map.visited()?.len().into())
+    }
+
+    fn solve_part_2(&self, map: &mut Self::Parsed) -> Result<Answer> {
+        // Only an obstacle somewhere on the guard's route can change where they go
+        let start = map.start.from;
+        let mut loops = 0;
+        for pos in map.visited()? {
+            if pos == start {
+                continue;
+            }
+
+            checkpoint()?;
+            map.add_obstacle(pos);
+            let leaves = map.patrol(|_, _| ());
+            map.remove_obstacle(pos);
+            if !leaves? {
+                loops += 1;
+            }
+        }
+
+        Ok(loops.into())
     }
 }
 
 #[derive(Debug)]
-struct Map {
+pub struct Map {
     start: TraverseFrom,
     rows: Vec<Vec<usize>>,
     cols: Vec<Vec<usize>>,
@@ -59,7 +83,7 @@ enum TraversedTo {
     Out(usize),
 }
 
-#[derive(Copy, Clone, Debug)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
 struct Pos {
     x: usize,
     y: usize,
@@ -69,9 +93,18 @@ impl Pos {
     fn new(x: usize, y: usize) -> Pos {
         Pos { x, y }
     }
+
+    fn step(self, direction: Direction, steps: usize) -> Pos {
+        match direction {
+            Direction::Up => Pos::new(self.x, self.y - steps),
+            Direction::Right => Pos::new(self.x + steps, self.y),
+            Direction::Down => Pos::new(self.x, self.y + steps),
+            Direction::Left => Pos::new(self.x - steps, self.y),
+        }
+    }
 }
 
-#[derive(Copy, Clone, Debug)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
 enum Direction {
     Up,
     Right,
@@ -79,6 +112,17 @@ enum Direction {
     Left,
 }
 
+impl Direction {
+    fn turn_right(self) -> Direction {
+        match self {
+            Direction::Up => Direction::Right,
+            Direction::Right => Direction::Down,
+            Direction::Down => Direction::Left,
+            Direction::Left => Direction::Up,
+        }
+    }
+}
+
 impl TryFrom<char> for Direction {
     type Error = Error;
 
@@ -95,11 +139,11 @@ impl TryFrom<char> for Direction {
 
 impl Map {
     fn parse(input: &str) -> Result<Self> {
-        let data = input.lines().map(|line| line.chars().collect()).collect();
+        let data: Vec<Vec<char>> = input.lines().map(|line| line.chars().collect()).collect();
 
         let width = data
-            .get(0)
-            .ok_or(Error::LineParseError("no data".into()))
+            .first()
+            .ok_or(Error::LineParseError("no data".into()))?
             .len();
         let height = data.len();
 
@@ -109,16 +153,18 @@ impl Map {
 
         for (y, chars) in data.iter().enumerate() {
             let mut row = vec![];
-            for (x, c) in chars {
-                match c {
+            for (x, c) in chars.iter().enumerate() {
+                match *c {
                     '#' => {
                         row.push(x);
                         cols.get_mut(x)
-                            .ok_or(Error::LineParseError("uneven input".into()))
+                            .ok_or(Error::LineParseError("uneven input".into()))?
                             .push(y);
                     },
                     '.' => (),
-                    dir if start.is_none() => start = Some(dir.into()?),
+                    dir if start.is_none() => {
+                        start = Some(TraverseFrom::new(Pos::new(x, y), dir.try_into()?))
+                    },
                     _ => return Err(Error::LineParseError("multiple starting positions".into())),
                 }
             }
@@ -128,17 +174,183 @@ impl Map {
 
         let start = start.ok_or(Error::LineParseError("no starting position".into()))?;
 
-        Self { start, rows, cols }
+        Ok(Self { start, rows, cols })
+    }
+
+    fn width(&self) -> usize {
+        self.cols.len()
+    }
+
+    fn height(&self) -> usize {
+        self.rows.len()
     }
 
+    /// Walk straight from `from` until the next obstacle, where the guard turns, or off the map
     fn traverse(&self, from: TraverseFrom) -> TraversedTo {
-        let next = match from.1 {
-            Direction::Up => self.cols[from.0 .0].iter().take(from.0 .1).rev().next(),
-            Direction::Right => self.rows[from.0 .1].iter().skip(from.0 .0 + 1).next(),
-            Direction::Down => self.cols[from.0 .0].iter().skip(from.0 .1 + 1).next(),
-            Direction::Left => self.rows[from.0 .1].iter().take(from.0 .0).rev().next(),
+        let Pos { x, y } = from.from;
+        let next = match from.direction {
+            Direction::Up => self.cols[x].iter().rev().find(|&&obstacle| obstacle < y),
+            Direction::Right => self.rows[y].iter().find(|&&obstacle| obstacle > x),
+            Direction::Down => self.cols[x].iter().find(|&&obstacle| obstacle > y),
+            Direction::Left => self.rows[y].iter().rev().find(|&&obstacle| obstacle < x),
         };
 
-        todo!()
+        let turn = from.direction.turn_right();
+        match (from.direction, next) {
+            (Direction::Up, Some(&o)) => TraversedTo::Obstacle(Pos::new(x, o + 1), turn, y - o - 1),
+            (Direction::Right, Some(&o)) => {
+                TraversedTo::Obstacle(Pos::new(o - 1, y), turn, o - x - 1)
+            },
+            (Direction::Down, Some(&o)) => {
+                TraversedTo::Obstacle(Pos::new(x, o - 1), turn, o - y - 1)
+            },
+            (Direction::Left, Some(&o)) => {
+                TraversedTo::Obstacle(Pos::new(o + 1, y), turn, x - o - 1)
+            },
+            (Direction::Up, None) => TraversedTo::Out(y),
+            (Direction::Right, None) => TraversedTo::Out(self.width() - x - 1),
+            (Direction::Down, None) => TraversedTo::Out(self.height() - y - 1),
+            (Direction::Left, None) => TraversedTo::Out(x),
+        }
+    }
+
+    /// Follow the guard's route from the start, calling `on_walk` with each straight stretch and
+    /// its length. Returns whether they leave the map, rather than going round in a loop.
+    fn patrol(&self, mut on_walk: impl FnMut(TraverseFrom, usize)) -> Result<bool> {
+        let mut turns = HashSet::new();
+        let mut from = self.start;
+
+        loop {
+            match self.traverse(from) {
+                TraversedTo::Obstacle(pos, direction, steps) => {
+                    on_walk(from, steps);
+                    // Turning the same way at the same place again means the route repeats
+                    if !turns.insert((pos, direction)) {
+                        return Ok(false);
+                    }
+                    checkpoint()?;
+                    from = TraverseFrom::new(pos, direction);
+                },
+                TraversedTo::Out(steps) => {
+                    on_walk(from, steps);
+                    return Ok(true);
+                },
+            }
+        }
+    }
+
+    /// Every position on the guard's route, once each
+    fn visited(&self) -> Result<Vec<Pos>> {
+        let mut seen = HashSet::new();
+        let mut visited = Vec::new();
+        let leaves = self.patrol(|from, steps| {
+            for step in 0..=steps {
+                let pos = from.from.step(from.direction, step);
+                if seen.insert(pos) {
+                    visited.push(pos);
+                }
+            }
+        })?;
+
+        if leaves {
+            Ok(visited)
+        } else {
+            Err(Error::NoSolutionError())
+        }
+    }
+
+    fn add_obstacle(&mut self, pos: Pos) {
+        let row = &mut self.rows[pos.y];
+        if let Err(i) = row.binary_search(&pos.x) {
+            row.insert(i, pos.x);
+        }
+        let col = &mut self.cols[pos.x];
+        if let Err(i) = col.binary_search(&pos.y) {
+            col.insert(i, pos.y);
+        }
+    }
+
+    fn remove_obstacle(&mut self, pos: Pos) {
+        self.rows[pos.y].retain(|&x| x != pos.x);
+        self.cols[pos.x].retain(|&y| y != pos.y);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use std::thread;
+    use std::time::Duration;
+
+    use itertools::Itertools;
+
+    use super::*;
+    use crate::challenge::cancel::Cancellation;
+    use crate::input::helpers::test_input;
+
+    /// A map where the guard spirals out from the middle, which takes a long time to check for
+    /// loops
+    fn spiral(size: isize) -> String {
+        let mut grid = vec![vec!['.'; size as usize]; size as usize];
+        let (mut x, mut y) = (size / 2, size / 2);
+        let (mut dx, mut dy) = (0, -1);
+        grid[y as usize][x as usize] = '^';
+
+        for leg in 0.. {
+            let steps = 2 * (leg / 2 + 1);
+            let (ox, oy) = (x + dx * (steps + 1), y + dy * (steps + 1));
+            if !(0..size).contains(&ox) || !(0..size).contains(&oy) {
+                break;
+            }
+
+            grid[oy as usize][ox as usize] = '#';
+            (x, y) = (x + dx * steps, y + dy * steps);
+            (dx, dy) = (-dy, dx);
+        }
+
+        grid.iter().map(|row| row.iter().collect::<String>()).join("\n")
+    }
+
+    #[test]
+    fn test_solve() {
+        let input = test_input(
+            r#"
+            ....#.....
+            .........#
+            ..........
+            ..#.......
+            .......#..
+            ..........
+            .#..^.....
+            ........#.
+            #.........
+            ......#...
+        "#,
+        );
+
+        let solver = Day6::new(InputHelper::new(2024, 6, Arc::new(input)));
+        let mut parsed = solver.parse().unwrap();
+        assert_eq!(solver.solve_part_1(&mut parsed).unwrap(), 41.into());
+        assert_eq!(solver.solve_part_2(&mut parsed).unwrap(), 6.into());
+    }
+
+    #[test]
+    fn test_cancel() {
+        let input = test_input(spiral(201));
+        let solver = Day6::new(InputHelper::new(2024, 6, Arc::new(input)));
+        let mut parsed = solver.parse().unwrap();
+
+        let cancellation = Cancellation::new();
+        let solving = thread::spawn({
+            let cancellation = cancellation.clone();
+            move || cancellation.scope(|| solver.solve_part_2(&mut parsed))
+        });
+
+        thread::sleep(Duration::from_millis(50));
+        cancellation.cancel();
+        assert!(matches!(
+            solving.join().unwrap(),
+            Err(Error::CancelledError())
+        ));
     }
 }
@@ -1,3 +1,5 @@
+use std::time::Duration;
+
 use thiserror::Error;
 
 use crate::input;
@@ -6,10 +8,12 @@ use crate::input;
 pub enum Error {
     #[error("invalid day `{0}`")]
     InvalidDay(usize),
-    #[error("day `{0}` not implemented")]
-    DayNotImplemented(usize),
+    #[error("{0} day `{1}` not implemented, pick another year with --year")]
+    DayNotImplemented(usize, usize),
     #[error("invalid line: {0}")]
     LineParseError(String),
+    #[error("error parsing input: {0}")]
+    ParseError(String),
     #[error("input error: {0}")]
     InputError(#[from] input::Error),
     #[error("error parsing int: {0}")]
@@ -26,6 +30,10 @@ pub enum Error {
     NoSolutionError(),
     #[error("solver panicked: {0}")]
     SolverPanicError(String),
+    #[error("timed out after {0:.2?}")]
+    TimeoutError(Duration),
+    #[error("cancelled")]
+    CancelledError(),
     #[error("unknown error")]
     #[default]
     UnknownError,
@@ -35,4 +43,25 @@ impl Error {
     pub fn missing_data(name: &str) -> Self {
         Error::MissingDataError(name.to_string())
     }
+
+    /// Short, stable name for the kind of error, for machine-readable output
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Error::InvalidDay(_) => "invalid_day",
+            Error::DayNotImplemented(..) => "not_implemented",
+            Error::LineParseError(_) => "line_parse",
+            Error::ParseError(_) => "parse",
+            Error::InputError(_) => "input",
+            Error::ParseIntError(_) => "parse_int",
+            Error::MissingDataError(_) => "missing_data",
+            Error::InvalidCommandError(_) => "invalid_command",
+            Error::InvalidDirectionError(_) => "invalid_direction",
+            Error::TooManyLinesError() => "too_many_lines",
+            Error::NoSolutionError() => "no_solution",
+            Error::SolverPanicError(_) => "panic",
+            Error::TimeoutError(_) => "timeout",
+            Error::CancelledError() => "cancelled",
+            Error::UnknownError => "unknown",
+        }
+    }
 }
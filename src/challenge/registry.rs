@@ -0,0 +1,106 @@
+use std::sync::Arc;
+
+use itertools::Itertools;
+
+use crate::challenge::{DynSolver, Solver};
+use crate::input::helpers::InputHelper;
+use crate::input::Input;
+
+pub const DEFAULT_YEAR: usize = 2024;
+
+type Constructor = fn(InputHelper) -> Box<dyn DynSolver>;
+
+/// A solver's entry in the registry, submitted by each day with [`register_solver!`]
+#[derive(Debug)]
+pub struct Registration {
+    pub year: usize,
+    pub day: usize,
+    pub title: Option<&'static str>,
+    constructor: Constructor,
+}
+
+inventory::collect!(Registration);
+
+impl Registration {
+    pub const fn new(
+        year: usize,
+        day: usize,
+        title: Option<&'static str>,
+        constructor: Constructor,
+    ) -> Self {
+        Self {
+            year,
+            day,
+            title,
+            constructor,
+        }
+    }
+
+    /// Make the solver, reading the input for the year and day it's registered for
+    pub fn construct(&self, input: Arc<dyn Input>) -> Box<dyn DynSolver> {
+        (self.constructor)(InputHelper::new(self.year, self.day, input))
+    }
+}
+
+pub fn construct<S: Solver + 'static>(input: InputHelper) -> Box<dyn DynSolver> {
+    Box::new(S::new(input))
+}
+
+/// All registered solvers, ordered by year and day
+pub fn registrations() -> Vec<&'static Registration> {
+    inventory::iter::<Registration>
+        .into_iter()
+        .sorted_by_key(|reg| (reg.year, reg.day))
+        .collect_vec()
+}
+
+pub fn find(year: usize, day: usize) -> Option<&'static Registration> {
+    inventory::iter::<Registration>
+        .into_iter()
+        .find(|reg| reg.year == year && reg.day == day)
+}
+
+pub fn is_registered(year: usize, day: usize) -> bool {
+    find(year, day).is_some()
+}
+
+/// Register a solver so it can be selected and run.
+///
+/// ```ignore
+/// register_solver!(Day1, day = 1, title = "Historian Hysteria");
+/// register_solver!(Day1, day = 1, year = 2023);
+/// ```
+macro_rules! register_solver {
+    ($solver:ty, day = $day:expr $(, year = $year:expr)? $(, title = $title:expr)? $(,)?) => {
+        inventory::submit! {
+            $crate::challenge::registry::Registration::new(
+                $crate::challenge::registry::register_solver!(@year $($year)?),
+                $day,
+                $crate::challenge::registry::register_solver!(@title $($title)?),
+                $crate::challenge::registry::construct::<$solver>,
+            )
+        }
+    };
+    (@year) => { $crate::challenge::registry::DEFAULT_YEAR };
+    (@year $year:expr) => { $year };
+    (@title) => { None };
+    (@title $title:expr) => { Some($title) };
+}
+
+pub(crate) use register_solver;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unique_registrations() {
+        let duplicates = registrations()
+            .into_iter()
+            .map(|reg| (reg.year, reg.day))
+            .duplicates()
+            .collect_vec();
+
+        assert!(duplicates.is_empty(), "duplicate solvers: {:?}", duplicates);
+    }
+}
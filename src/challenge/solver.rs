@@ -1,36 +1,130 @@
 use std::cmp::Ordering;
 use std::fmt::{Debug, Display, Formatter};
-use std::sync::{Arc, Mutex};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 
-use paste::paste;
-use tokio::task::{spawn_blocking, JoinSet};
+use itertools::Itertools;
+use tokio::sync::mpsc;
+use tokio::task::spawn_blocking;
+use tokio::time::timeout_at;
 
+use crate::answers::Verdict;
+use crate::challenge::cancel::Cancellation;
 use crate::challenge::*;
 use crate::error::thread_panic_string;
+use crate::input::helpers::InputHelper;
 use crate::input::Input;
 use crate::select::{Challenge, ChallengePart};
 
+/// Solves both parts of a day's challenge.
+///
+/// Solvers run on a blocking thread and are abandoned if they time out, so long searches should
+/// call [`cancel::checkpoint`] regularly to stop once nobody is waiting for their answer.
 pub trait Solver: Debug + Send + Sync {
-    fn new(input: Arc<Mutex<dyn Input>>) -> Self
+    /// Input representation shared by both parts. When both parts are selected, part 1 runs
+    /// first and may leave intermediate results here for part 2 to reuse.
+    type Parsed;
+
+    /// Make the solver for the day it's registered for, reading that day's input through `input`
+    fn new(input: InputHelper) -> Self
     where
         Self: Sized;
 
-    fn solve_part_1(&self) -> Result<String>;
-    fn solve_part_2(&self) -> Result<String>;
+    fn parse(&self) -> Result<Self::Parsed>;
+    fn solve_part_1(&self, parsed: &mut Self::Parsed) -> Result<Answer>;
+    fn solve_part_2(&self, parsed: &mut Self::Parsed) -> Result<Answer>;
 
-    fn solve(&self, part: ChallengePart) -> Result<String> {
+    fn solve(&self, part: ChallengePart, parsed: &mut Self::Parsed) -> Result<Answer> {
         use ChallengePart::*;
         match part {
-            First => self.solve_part_1(),
-            Second => self.solve_part_2(),
+            First => self.solve_part_1(parsed),
+            Second => self.solve_part_2(parsed),
         }
     }
 }
 
+/// Type-erased [`Solver`] which parses its input once and solves each selected part of the day
+pub trait DynSolver: Debug + Send + Sync {
+    /// Solve each challenge in order, handing over every solution as soon as it is ready
+    fn solve_each(&self, challenges: &[Challenge], emit: &mut dyn FnMut(Solution));
+
+    fn solve_day(&self, challenges: &[Challenge]) -> Vec<Solution> {
+        let mut solutions = Vec::with_capacity(challenges.len());
+        self.solve_each(challenges, &mut |solution| solutions.push(solution));
+        solutions
+    }
+
+    fn solve_one(&self, challenge: Challenge) -> Solution {
+        self.solve_day(&[challenge])
+            .pop()
+            .expect("one solution per challenge")
+    }
+}
+
+impl<S: Solver> DynSolver for S {
+    fn solve_each(&self, challenges: &[Challenge], emit: &mut dyn FnMut(Solution)) {
+        let start = Instant::now();
+        let parsed = self.parse();
+        let parse = start.elapsed();
+
+        let mut parsed = match parsed {
+            Ok(parsed) => parsed,
+            Err(err) => {
+                let message = err.to_string();
+                for challenge in challenges.iter().sorted() {
+                    emit(Solution {
+                        challenge: *challenge,
+                        solution: Err(Error::ParseError(message.clone())),
+                        timings: Timings::new(parse, Duration::ZERO),
+                        verdict: None,
+                    });
+                }
+                return;
+            },
+        };
+
+        for challenge in challenges.iter().sorted() {
+            let start = Instant::now();
+            let solution =
+                cancel::checkpoint().and_then(|()| self.solve(challenge.part, &mut parsed));
+
+            emit(Solution {
+                challenge: *challenge,
+                solution,
+                timings: Timings::new(parse, start.elapsed()),
+                verdict: None,
+            });
+        }
+    }
+}
+
+#[derive(Debug, Default, Copy, Clone)]
+pub struct Timings {
+    pub parse: Duration,
+    pub solve: Duration,
+}
+
+impl Timings {
+    pub fn new(parse: Duration, solve: Duration) -> Self {
+        Self { parse, solve }
+    }
+}
+
+/// Limits on how long solvers may run before they are cancelled
+#[derive(Debug, Default, Copy, Clone)]
+pub struct Timeouts {
+    /// Time allowed for each challenge, including parsing for the first part of a day
+    pub challenge: Option<Duration>,
+    /// Time allowed for the whole selection
+    pub total: Option<Duration>,
+}
+
 #[derive(Debug)]
 pub struct Solution {
-    challenge: Challenge,
-    solution: Result<String>,
+    pub challenge: Challenge,
+    pub solution: Result<Answer>,
+    pub timings: Timings,
+    pub verdict: Option<Verdict>,
 }
 
 impl Solution {
@@ -38,6 +132,8 @@ impl Solution {
         Self {
             challenge,
             solution: Err(err),
+            timings: Timings::default(),
+            verdict: None,
         }
     }
 }
@@ -65,73 +161,313 @@ impl Ord for Solution {
 impl Display for Solution {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         match &self.solution {
-            Ok(solution) => write!(f, "{}: {}", self.challenge, solution),
+            Ok(solution) => {
+                write!(f, "{}: {}", self.challenge, solution)?;
+                if let Some(verdict) = &self.verdict {
+                    write!(f, " [{}]", verdict)?;
+                }
+                write!(
+                    f,
+                    " (parse {:?}, solve {:?})",
+                    self.timings.parse, self.timings.solve
+                )
+            },
             Err(err) => write!(f, "{}: ERROR {}", self.challenge, err),
         }
     }
 }
 
-pub async fn solve(challenge: Challenge, input: Arc<Mutex<dyn Input>>) -> Solution {
-    let solver = get_challenge(challenge.day, input);
-    let Ok(solver) = solver else {
-        return Solution::error(challenge, solver.unwrap_err());
+/// Progress of a challenge while its day is being solved
+#[derive(Debug)]
+pub enum Progress {
+    Running(Challenge),
+    Done(Solution),
+}
+
+type Events = mpsc::UnboundedSender<Progress>;
+
+pub async fn solve_day(
+    year: usize,
+    day: usize,
+    challenges: Vec<Challenge>,
+    input: Arc<dyn Input>,
+    timeout: Option<Duration>,
+    deadline: Option<tokio::time::Instant>,
+    events: Events,
+) {
+    let Some(solver) = get_challenge(year, day, input) else {
+        for challenge in challenges {
+            let _ = events.send(Progress::Done(Solution::error(
+                challenge,
+                missing_solver(year, day),
+            )));
+        }
+        return;
     };
 
-    let solution = spawn_blocking(move || {
-        let solution = solver.solve(challenge.part);
-        Solution {
-            challenge,
-            solution,
+    run_solver(solver, challenges, timeout, deadline, events).await
+}
+
+/// Run a day's solver on a blocking thread, giving each challenge up to `timeout` to finish and
+/// cancelling the solver when that, or the overall `deadline`, passes
+async fn run_solver(
+    solver: Box<dyn DynSolver>,
+    mut challenges: Vec<Challenge>,
+    timeout: Option<Duration>,
+    deadline: Option<tokio::time::Instant>,
+    events: Events,
+) {
+    challenges.sort();
+
+    let cancellation = Cancellation::new();
+    let (sender, mut receiver) = mpsc::unbounded_channel();
+    let handle = spawn_blocking({
+        let challenges = challenges.clone();
+        let cancellation = cancellation.clone();
+        move || {
+            cancellation.scope(|| {
+                solver.solve_each(&challenges, &mut |solution| {
+                    let _ = sender.send(solution);
+                })
+            })
         }
-    })
-        .await;
+    });
 
-    let Ok(solution) = solution else {
-        return Solution::error(
-            challenge,
-            Error::SolverPanicError(thread_panic_string(solution.unwrap_err())),
-        );
-    };
+    let mut solved = 0;
+    let mut started = tokio::time::Instant::now();
+    let mut timed_out = false;
+    while solved < challenges.len() {
+        started = tokio::time::Instant::now();
+        let _ = events.send(Progress::Running(challenges[solved]));
+
+        let limit = [timeout.map(|timeout| started + timeout), deadline]
+            .into_iter()
+            .flatten()
+            .min();
+
+        let received = match limit {
+            Some(limit) => timeout_at(limit, receiver.recv()).await,
+            None => Ok(receiver.recv().await),
+        };
+
+        match received {
+            Ok(Some(solution)) => {
+                solved += 1;
+                let _ = events.send(Progress::Done(solution));
+            },
+            Ok(None) => break,
+            Err(_) => {
+                cancellation.cancel();
+                timed_out = true;
+                break;
+            },
+        }
+    }
+
+    // Solutions arrive in order, so the first unsolved challenge is the one that was running
+    let unsolved = challenges.split_off(solved);
+    if timed_out {
+        // The solver keeps its thread until it notices the cancellation, there's no waiting for it
+        let elapsed = started.elapsed();
+        for (n, challenge) in unsolved.into_iter().enumerate() {
+            let err = if n == 0 {
+                Error::TimeoutError(elapsed)
+            } else {
+                Error::CancelledError()
+            };
+            let _ = events.send(Progress::Done(Solution::error(challenge, err)));
+        }
+    } else if !unsolved.is_empty() {
+        let message = match handle.await {
+            Ok(()) => "no solution produced".to_string(),
+            Err(err) => thread_panic_string(err),
+        };
+        for challenge in unsolved {
+            let _ = events.send(Progress::Done(Solution::error(
+                challenge,
+                Error::SolverPanicError(message.clone()),
+            )));
+        }
+    }
+}
+
+/// Solve every day in the selection concurrently, reporting each challenge as it starts and
+/// finishes. The stream ends once every challenge is done.
+pub fn solve_stream(
+    challenges: Vec<Challenge>,
+    input: Arc<dyn Input>,
+    timeouts: Timeouts,
+) -> mpsc::UnboundedReceiver<Progress> {
+    let deadline = timeouts
+        .total
+        .map(|total| tokio::time::Instant::now() + total);
+
+    let (events, receiver) = mpsc::unbounded_channel();
+    for ((year, day), challenges) in &challenges
+        .into_iter()
+        .sorted()
+        .dedup()
+        .chunk_by(|challenge| (challenge.year, challenge.day))
+    {
+        tokio::spawn(solve_day(
+            year,
+            day,
+            challenges.collect_vec(),
+            input.clone(),
+            timeouts.challenge,
+            deadline,
+            events.clone(),
+        ));
+    }
 
-    solution
+    receiver
 }
 
 pub async fn solve_all(
     challenges: Vec<Challenge>,
-    input: Arc<Mutex<impl Input + 'static>>,
+    input: Arc<dyn Input>,
+    timeouts: Timeouts,
 ) -> Vec<Solution> {
-    let mut join_set = JoinSet::new();
-    for challenge in challenges {
-        join_set.spawn(solve(challenge, input.clone()));
-    }
+    let mut progress = solve_stream(challenges, input, timeouts);
 
     let mut result = Vec::new();
-    while let Some(solution) = join_set.join_next().await {
-        let Ok(solution) = solution else {
-            panic!("unhandled join error: {:?}", solution.unwrap_err())
-        };
-
-        result.push(solution);
+    while let Some(event) = progress.recv().await {
+        if let Progress::Done(solution) = event {
+            result.push(solution);
+        }
     }
 
     result.sort();
     result
 }
 
-macro_rules! solver_inst {
-    ($day:tt, $input:ident) => {{
-        Ok(Box::new(<paste! { [<day $day>]::[<Day $day>] }>::new($input)) as Box<dyn Solver>)
-    }};
+pub(crate) fn get_challenge(
+    year: usize,
+    day: usize,
+    input: Arc<dyn Input>,
+) -> Option<Box<dyn DynSolver>> {
+    registry::find(year, day).map(|registration| registration.construct(input))
 }
 
-fn get_challenge<'a>(day: usize, input: Arc<Mutex<dyn Input>>) -> Result<Box<dyn Solver>> {
-    match day {
-        1 => solver_inst!(1, input),
-        2 => solver_inst!(2, input),
-        3 => solver_inst!(3, input),
-        4 => solver_inst!(4, input),
-        5 => solver_inst!(5, input),
-        day if day > 25 => Err(Error::InvalidDay(day)),
-        day => Err(Error::DayNotImplemented(day)),
+pub(crate) fn missing_solver(year: usize, day: usize) -> Error {
+    if day == 0 || day > 25 {
+        Error::InvalidDay(day)
+    } else {
+        Error::DayNotImplemented(year, day)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    use super::*;
+
+    /// Answers part 1 straight away, and spins on part 2 until it is cancelled
+    #[derive(Debug, Default)]
+    struct Spinner {
+        stopped: Arc<AtomicBool>,
+    }
+
+    impl Solver for Spinner {
+        type Parsed = ();
+
+        fn new(_input: InputHelper) -> Self {
+            Self::default()
+        }
+
+        fn parse(&self) -> Result<Self::Parsed> {
+            Ok(())
+        }
+
+        fn solve_part_1(&self, _parsed: &mut Self::Parsed) -> Result<Answer> {
+            Ok(1.into())
+        }
+
+        fn solve_part_2(&self, _parsed: &mut Self::Parsed) -> Result<Answer> {
+            let result = loop {
+                if let Err(err) = cancel::checkpoint() {
+                    break Err(err);
+                }
+                std::thread::sleep(Duration::from_millis(1));
+            };
+
+            self.stopped.store(true, Ordering::Relaxed);
+            result
+        }
+    }
+
+    async fn run(
+        solver: Spinner,
+        challenges: Vec<Challenge>,
+        timeout: Option<Duration>,
+        deadline: Option<tokio::time::Instant>,
+    ) -> (Vec<Challenge>, Vec<Solution>) {
+        let (events, mut receiver) = mpsc::unbounded_channel();
+        run_solver(Box::new(solver), challenges, timeout, deadline, events).await;
+
+        let mut running = Vec::new();
+        let mut solutions = Vec::new();
+        while let Some(event) = receiver.recv().await {
+            match event {
+                Progress::Running(challenge) => running.push(challenge),
+                Progress::Done(solution) => solutions.push(solution),
+            }
+        }
+
+        (running, solutions)
+    }
+
+    async fn wait_for(flag: &AtomicBool) {
+        for _ in 0..100 {
+            if flag.load(Ordering::Relaxed) {
+                return;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+
+        panic!("solver was not cancelled");
+    }
+
+    #[tokio::test]
+    async fn test_challenge_timeout() {
+        let spinner = Spinner::default();
+        let stopped = spinner.stopped.clone();
+        let challenges = vec![
+            Challenge::new(2024, 1, ChallengePart::Second),
+            Challenge::new(2024, 1, ChallengePart::First),
+        ];
+
+        let (running, solutions) = run(
+            spinner,
+            challenges.clone(),
+            Some(Duration::from_millis(50)),
+            None,
+        )
+        .await;
+
+        assert_eq!(running, challenges.into_iter().rev().collect_vec());
+        assert_eq!(solutions.len(), 2);
+        assert_eq!(solutions[0].solution.as_ref().unwrap(), &Answer::from(1));
+        assert!(matches!(solutions[1].solution, Err(Error::TimeoutError(_))));
+        wait_for(&stopped).await;
+    }
+
+    #[tokio::test]
+    async fn test_deadline() {
+        let spinner = Spinner::default();
+        let stopped = spinner.stopped.clone();
+        let deadline = tokio::time::Instant::now() + Duration::from_millis(50);
+
+        let (_, solutions) = run(
+            spinner,
+            vec![Challenge::new(2024, 1, ChallengePart::Second)],
+            Some(Duration::from_secs(60)),
+            Some(deadline),
+        )
+        .await;
+
+        assert!(matches!(solutions[0].solution, Err(Error::TimeoutError(_))));
+        wait_for(&stopped).await;
     }
 }
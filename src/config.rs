@@ -0,0 +1,51 @@
+use std::fs;
+use std::io::ErrorKind;
+use std::path::PathBuf;
+
+use crate::calendar;
+use crate::error::Error;
+
+const CONFIG_DIR: &str = "aoc24";
+const YEAR_FILE: &str = "year";
+
+/// Where the setting `name` is kept in the user's config directory, e.g.
+/// `~/.config/aoc24/session`
+pub fn user_config_path(name: &str) -> Option<PathBuf> {
+    dirs::config_dir().map(|dir| dir.join(CONFIG_DIR).join(name))
+}
+
+/// The year set in the user's config, if any
+pub fn default_year() -> Result<Option<usize>, Error> {
+    let Some(path) = user_config_path(YEAR_FILE) else {
+        return Ok(None);
+    };
+
+    let text = match fs::read_to_string(&path) {
+        Ok(text) => text,
+        Err(err) if err.kind() == ErrorKind::NotFound => return Ok(None),
+        Err(err) => return Err(err.into()),
+    };
+
+    parse_year(&text)
+        .map(Some)
+        .ok_or_else(|| Error::ConfigError(format!("{}: invalid year", path.display())))
+}
+
+fn parse_year(text: &str) -> Option<usize> {
+    text.trim()
+        .parse()
+        .ok()
+        .filter(|year| *year >= calendar::FIRST_YEAR)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_year() {
+        assert_eq!(parse_year("2023\n"), Some(2023));
+        assert_eq!(parse_year("2014"), None);
+        assert_eq!(parse_year("next year"), None);
+    }
+}
@@ -15,6 +15,14 @@ pub enum Error {
     ChallengeError(#[from] challenge::Error),
     #[error("input error: {0}")]
     InputError(#[from] input::Error),
+    #[error("{0} can't be used to {1}, as accepted answers are for the real input")]
+    InputOptionError(&'static str, &'static str),
+    #[error("config error: {0}")]
+    ConfigError(String),
+    #[error("known answers error: {0}")]
+    KnownAnswersError(String),
+    #[error("io error: {0}")]
+    IOError(#[from] std::io::Error),
 }
 
 pub fn thread_panic_string(err: JoinError) -> String {
@@ -1,21 +1,80 @@
+mod client;
 mod download;
+mod encrypted;
 mod error;
 pub mod helpers;
 mod input_files;
+mod input_override;
+mod manifest;
+#[cfg(test)]
+mod mock;
+mod puzzle;
+mod session;
+#[cfg(test)]
 mod simple;
+mod submit;
 
 use std::fmt::Debug;
+use std::io::{self, BufRead, Cursor};
+use std::sync::Arc;
 
+pub use client::{Client, ClientOptions, RetryPolicy, DEFAULT_BASE_URL};
 pub use download::Download;
+pub use encrypted::{EncryptedInput, Key, KEY_FILE};
 pub use error::Error;
-pub use input_files::InputFiles;
+pub use input_files::{InputFiles, InputSet};
+pub use input_override::{InputOverride, InputSource};
+pub use manifest::{check, Manifest};
+pub use puzzle::Puzzle;
+pub use session::Session;
+pub use submit::{Attempt, Outcome, SubmissionLog, Submitter};
 
 #[cfg(test)]
 pub use simple::SimpleInput;
 
 pub type Result<T> = std::result::Result<T, Error>;
 
+/// Each day's input text, shared between solvers without any locking
 pub trait Input: Debug + Send + Sync {
-    fn has_input(&self, day: usize) -> bool;
-    fn get_input(&mut self, day: usize) -> Result<String>;
+    fn has_input(&self, year: usize, day: usize) -> bool;
+
+    /// The whole input as text, failing if it isn't UTF-8
+    fn get_input(&self, year: usize, day: usize) -> Result<Arc<str>>;
+
+    /// The whole input as it was saved, for solvers which don't need it checked as text
+    fn get_bytes(&self, year: usize, day: usize) -> Result<Arc<[u8]>>;
+
+    /// Stream the input rather than holding it all in memory at once, e.g. through [`Lines`]
+    fn reader(&self, year: usize, day: usize) -> Result<Box<dyn BufRead + Send>> {
+        Ok(Box::new(Cursor::new(self.get_bytes(year, day)?)))
+    }
+}
+
+/// Each line of an input's [`Input::reader`] in turn, without its line ending
+pub struct Lines {
+    year: usize,
+    day: usize,
+    lines: io::Lines<Box<dyn BufRead + Send>>,
+}
+
+impl Lines {
+    pub fn new(year: usize, day: usize, reader: Box<dyn BufRead + Send>) -> Self {
+        Self {
+            year,
+            day,
+            lines: reader.lines(),
+        }
+    }
+}
+
+impl Iterator for Lines {
+    type Item = Result<String>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let line = self.lines.next()?;
+        Some(line.map_err(|err| match err.kind() {
+            io::ErrorKind::InvalidData => Error::Utf8Error(self.year, self.day),
+            _ => err.into(),
+        }))
+    }
 }
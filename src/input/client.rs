@@ -0,0 +1,319 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use rand::Rng;
+use reqwest::{IntoUrl, Method, RequestBuilder, StatusCode};
+use tokio::sync::{Mutex, Semaphore, SemaphorePermit};
+use tokio::time::Instant;
+use url::Url;
+
+use crate::input::{Error, Result};
+
+pub const DEFAULT_BASE_URL: &str = "https://adventofcode.com";
+
+/// Limits on how hard we hit Advent of Code
+#[derive(Debug, Clone)]
+pub struct ClientOptions {
+    /// Root of the Advent of Code site, which every request is made relative to
+    pub base_url: Url,
+    /// Who to contact about misbehaving requests, included in the User-Agent
+    pub contact: Option<String>,
+    /// Minimum time between the start of one request and the next
+    pub min_interval: Duration,
+    /// Most requests in flight at once
+    pub max_concurrent: usize,
+}
+
+impl Default for ClientOptions {
+    fn default() -> Self {
+        Self {
+            base_url: Url::parse(DEFAULT_BASE_URL).expect("valid default base url"),
+            contact: None,
+            min_interval: Duration::from_secs(1),
+            max_concurrent: 2,
+        }
+    }
+}
+
+/// How often and how patiently to retry requests that failed for reasons likely to pass
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    /// Most attempts at a request, including the first
+    pub max_attempts: usize,
+    /// Wait before the first retry, doubled for each one after
+    pub base_delay: Duration,
+    /// Longest wait between attempts
+    pub max_delay: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 4,
+            base_delay: Duration::from_millis(500),
+            max_delay: Duration::from_secs(10),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Wait before the attempt after `attempt`, backing off exponentially with up to half of it
+    /// random, so clients that failed together don't all retry together
+    pub fn delay(&self, attempt: usize) -> Duration {
+        let exponent = attempt.saturating_sub(1).min(16) as u32;
+        let delay = self
+            .base_delay
+            .saturating_mul(1 << exponent)
+            .min(self.max_delay);
+
+        delay.mul_f64(rand::thread_rng().gen_range(0.5..=1.0))
+    }
+
+    /// Whether a request that ended this way is worth trying again. Server errors and broken
+    /// connections often are; client errors never are, as the same request will fail the same way.
+    fn should_retry(result: &Result<Response>) -> bool {
+        match result {
+            Ok(res) => res.status.is_server_error(),
+            Err(Error::HttpError(err)) => {
+                err.is_connect() || err.is_timeout() || err.is_request() || err.is_body()
+            },
+            Err(_) => false,
+        }
+    }
+}
+
+/// HTTP client shared by everything that talks to Advent of Code, which identifies itself and
+/// keeps requests spaced out and few at a time
+#[derive(Debug, Clone)]
+pub struct Client {
+    inner: reqwest::Client,
+    base_url: Url,
+    throttle: Arc<Throttle>,
+}
+
+/// A response whose body has been read in full, so the request's slot is free again
+#[derive(Debug)]
+pub struct Response {
+    pub status: StatusCode,
+    pub body: Vec<u8>,
+}
+
+impl Client {
+    pub fn new(options: &ClientOptions) -> Result<Self> {
+        let inner = reqwest::Client::builder()
+            .use_rustls_tls()
+            .user_agent(Self::user_agent(options.contact.as_deref()))
+            .build()?;
+
+        Ok(Self {
+            inner,
+            base_url: options.base_url.clone(),
+            throttle: Arc::new(Throttle::new(options.min_interval, options.max_concurrent)),
+        })
+    }
+
+    /// `aoc24/0.1.0 (contact)`, with the crate's authors as the contact unless given another
+    pub fn user_agent(contact: Option<&str>) -> String {
+        let contact = contact.unwrap_or(env!("CARGO_PKG_AUTHORS"));
+        format!(
+            "{}/{} ({})",
+            env!("CARGO_PKG_NAME"),
+            env!("CARGO_PKG_VERSION"),
+            contact
+        )
+    }
+
+    /// The URL of `segments` under the base URL, e.g. `["2024", "day", "1"]`
+    pub fn url(&self, segments: &[&str]) -> Result<Url> {
+        let mut url = self.base_url.clone();
+        url.path_segments_mut()?.pop_if_empty().extend(segments);
+
+        Ok(url)
+    }
+
+    pub fn get(&self, url: impl IntoUrl) -> RequestBuilder {
+        self.inner.request(Method::GET, url)
+    }
+
+    pub fn post(&self, url: impl IntoUrl) -> RequestBuilder {
+        self.inner.request(Method::POST, url)
+    }
+
+    /// Send a request once the throttle allows it, and read the whole response
+    pub async fn execute(&self, request: reqwest::Request) -> Result<Response> {
+        let _permit = self.throttle.acquire().await;
+
+        let res = self.inner.execute(request).await?;
+        let status = res.status();
+        let body = res.bytes().await?.to_vec();
+
+        Ok(Response { status, body })
+    }
+
+    /// Send a request, retrying it under `policy` while it fails transiently. Returns the last
+    /// result along with how many attempts were made.
+    pub async fn execute_with_retry(
+        &self,
+        request: reqwest::Request,
+        policy: &RetryPolicy,
+    ) -> (Result<Response>, usize) {
+        let mut attempt = 1;
+        loop {
+            // Requests with a streaming body can't be replayed, so only get one attempt
+            let Some(this_attempt) = request.try_clone() else {
+                return (self.execute(request).await, attempt);
+            };
+
+            let result = self.execute(this_attempt).await;
+            if attempt >= policy.max_attempts || !RetryPolicy::should_retry(&result) {
+                return (result, attempt);
+            }
+
+            tokio::time::sleep(policy.delay(attempt)).await;
+            attempt += 1;
+        }
+    }
+}
+
+impl Response {
+    pub fn text(&self) -> String {
+        String::from_utf8_lossy(&self.body).into_owned()
+    }
+}
+
+#[derive(Debug)]
+struct Throttle {
+    permits: Semaphore,
+    next: Mutex<Instant>,
+    min_interval: Duration,
+}
+
+impl Throttle {
+    fn new(min_interval: Duration, max_concurrent: usize) -> Self {
+        Self {
+            permits: Semaphore::new(max_concurrent.max(1)),
+            next: Mutex::new(Instant::now()),
+            min_interval,
+        }
+    }
+
+    async fn acquire(&self) -> SemaphorePermit<'_> {
+        let permit = self
+            .permits
+            .acquire()
+            .await
+            .expect("throttle semaphore is never closed");
+
+        // Hold the lock while waiting, so requests queue up one interval apart
+        let mut next = self.next.lock().await;
+        tokio::time::sleep_until(*next).await;
+        *next = Instant::now() + self.min_interval;
+
+        permit
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::input::mock::{MockServer, Reply, RunningServer};
+
+    /// Server which answers successive requests for `/` with each of `statuses` in turn
+    async fn serve_statuses(statuses: &[u16]) -> RunningServer {
+        statuses
+            .iter()
+            .fold(MockServer::new(), |server, &status| {
+                server.route("GET", "/", Reply::new(status, "ok"))
+            })
+            .start()
+            .await
+    }
+
+    async fn execute(statuses: &[u16]) -> (Result<Response>, usize) {
+        let server = serve_statuses(statuses).await;
+        let client = server.client();
+        let policy = RetryPolicy {
+            max_attempts: 3,
+            base_delay: Duration::from_millis(1),
+            max_delay: Duration::from_millis(5),
+        };
+
+        client
+            .execute_with_retry(
+                client.get(client.url(&[]).unwrap()).build().unwrap(),
+                &policy,
+            )
+            .await
+    }
+
+    #[test]
+    fn test_user_agent() {
+        assert!(Client::user_agent(None).starts_with("aoc24/"));
+        assert!(Client::user_agent(Some("me@example.com")).ends_with("(me@example.com)"));
+    }
+
+    #[tokio::test]
+    async fn test_throttle() {
+        let throttle = Throttle::new(Duration::from_millis(50), 1);
+        let start = Instant::now();
+
+        for _ in 0..3 {
+            let _permit = throttle.acquire().await;
+        }
+        assert!(start.elapsed() >= Duration::from_millis(100));
+
+        let permit = throttle.acquire().await;
+        assert_eq!(throttle.permits.available_permits(), 0);
+        drop(permit);
+        assert_eq!(throttle.permits.available_permits(), 1);
+    }
+
+    #[test]
+    fn test_url() {
+        let client = Client::new(&ClientOptions::default()).unwrap();
+        assert_eq!(
+            client.url(&["2024", "day", "1"]).unwrap().as_str(),
+            "https://adventofcode.com/2024/day/1"
+        );
+
+        let client = Client::new(&ClientOptions {
+            base_url: Url::parse("http://localhost:8080/aoc/").unwrap(),
+            ..Default::default()
+        })
+        .unwrap();
+        assert_eq!(
+            client.url(&["2024"]).unwrap().as_str(),
+            "http://localhost:8080/aoc/2024"
+        );
+    }
+
+    #[test]
+    fn test_retry_delay() {
+        let policy = RetryPolicy {
+            max_attempts: 10,
+            base_delay: Duration::from_millis(100),
+            max_delay: Duration::from_millis(500),
+        };
+
+        let first = policy.delay(1);
+        assert!(first >= Duration::from_millis(50) && first <= Duration::from_millis(100));
+        let third = policy.delay(3);
+        assert!(third >= Duration::from_millis(200) && third <= Duration::from_millis(400));
+        assert!(policy.delay(50) <= Duration::from_millis(500));
+    }
+
+    #[tokio::test]
+    async fn test_retry() {
+        let (res, attempts) = execute(&[503, 502, 200]).await;
+        assert_eq!(res.unwrap().status, StatusCode::OK);
+        assert_eq!(attempts, 3);
+
+        let (res, attempts) = execute(&[500, 500, 500, 200]).await;
+        assert_eq!(res.unwrap().status, StatusCode::INTERNAL_SERVER_ERROR);
+        assert_eq!(attempts, 3);
+
+        let (res, attempts) = execute(&[404, 200]).await;
+        assert_eq!(res.unwrap().status, StatusCode::NOT_FOUND);
+        assert_eq!(attempts, 1);
+    }
+}
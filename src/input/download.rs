@@ -1,104 +1,655 @@
-use std::path::PathBuf;
+use std::io::IsTerminal;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
 
+use chrono::{DateTime, Local, Utc};
 use itertools::Itertools;
+use reqwest::StatusCode;
 use tokio::fs;
 use tokio::task::JoinSet;
-use url::Url;
 
+use crate::calendar;
 use crate::input::input_files::InputFiles;
-use crate::input::{Error, Input, Result};
+use crate::input::manifest::{self, Manifest, ManifestEntry};
+use crate::input::{Client, Error, Input, Key, Puzzle, Result, RetryPolicy, Session};
 use crate::select::Challenge;
 
-const BASE_URI: &'static str = "https://adventofcode.com/2024";
-const SESSION_FILE: &'static str = ".session";
-
 #[derive(Debug)]
 pub struct Download {
     input: InputFiles,
-    client: reqwest::Client,
-    session: String,
+    client: Client,
+    session: Session,
+    retry: RetryPolicy,
+    key: Option<Key>,
 }
 
-impl Download {
-    pub async fn new(input: &InputFiles) -> Result<Self> {
-        use std::io::ErrorKind::*;
-
-        let client = reqwest::Client::builder().use_rustls_tls().build()?;
-
-        let session = fs::read_to_string(SESSION_FILE)
-            .await
-            .map(|contents| contents.lines().next().unwrap_or_default().to_string())
-            .map_err(|err| match err.kind() {
-                NotFound => Error::NoSessionFile,
-                _ => Error::IOError(err),
-            })?;
+/// A day whose input was downloaded, and how many attempts that took
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Downloaded {
+    pub year: usize,
+    pub day: usize,
+    pub attempts: usize,
+}
 
-        Ok(Self {
+impl Download {
+    pub fn new(input: &InputFiles, session: Session, client: Client) -> Self {
+        Self {
             input: input.clone(),
             client,
             session,
-        })
+            retry: RetryPolicy::default(),
+            key: None,
+        }
+    }
+
+    /// Save inputs encrypted with `key`, counting ones already saved that way as downloaded
+    pub fn with_key(mut self, key: Option<Key>) -> Self {
+        self.key = key;
+        self
+    }
+
+    fn has_input(&self, year: usize, day: usize) -> bool {
+        let encrypted = self.key.is_some() && self.input.encrypted_path(year, day).exists();
+        encrypted || self.input.has_input(year, day)
+    }
+
+    pub fn client(&self) -> &Client {
+        &self.client
+    }
+
+    pub fn session(&self) -> &Session {
+        &self.session
+    }
+
+    /// Check the session is still logged in, returning the user's name
+    pub async fn check_session(&self, year: usize) -> Result<String> {
+        self.session.check(&self.client, year).await
     }
 
-    pub async fn download_missing(&self, selection: &[Challenge]) -> Result<()> {
+    /// The puzzle description for `day` of `year`, from the cache next to its input unless
+    /// `refresh` is set or there is nothing cached yet
+    pub async fn puzzle(&self, year: usize, day: usize, refresh: bool) -> Result<Puzzle> {
+        if !refresh {
+            if let Some(puzzle) = self.cached_puzzle(year, day).await? {
+                return Ok(puzzle);
+            }
+        }
+
+        let url = self
+            .client
+            .url(&[&year.to_string(), "day", &day.to_string()])?;
+        let req = self
+            .client
+            .get(url)
+            .header("Cookie", self.session.cookie())
+            .build()?;
+
+        let (res, _) = self.client.execute_with_retry(req, &self.retry).await;
+        let res = res?;
+        match res.status {
+            StatusCode::NOT_FOUND => return Err(Error::NotUnlocked(year, day)),
+            status if status.is_server_error() => return Err(Error::ServerError(status)),
+            _ => (),
+        }
+
+        let puzzle = Puzzle::parse_page(year, day, &res.text())?;
+        let path = self.input.puzzle_path(year, day);
+        write_atomic(&path, puzzle.to_markdown().as_bytes()).await?;
+        self.save_examples(&puzzle).await?;
+
+        Ok(puzzle)
+    }
+
+    async fn cached_puzzle(&self, year: usize, day: usize) -> Result<Option<Puzzle>> {
+        match fs::read_to_string(self.input.puzzle_path(year, day)).await {
+            Ok(markdown) => Ok(Some(Puzzle::from_markdown(year, day, &markdown))),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    async fn save_examples(&self, puzzle: &Puzzle) -> Result<()> {
+        for (i, example) in puzzle.examples().iter().enumerate() {
+            let path = self.input.example_path(puzzle.year, puzzle.day, i + 1);
+            write_atomic(&path, example.as_bytes()).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Save the examples from the puzzle for every selected day that doesn't have them yet,
+    /// fetching the puzzle if it isn't cached
+    pub async fn download_examples(&self, selection: &[Challenge], example: usize) -> Result<()> {
+        let missing_days = selection
+            .iter()
+            .map(|challenge| (challenge.year, challenge.day))
+            .sorted()
+            .dedup()
+            .filter(|&(year, day)| !self.input.example_path(year, day, example).exists())
+            .collect_vec();
+
+        for (year, day) in missing_days {
+            // Fetching a puzzle saves its examples, but one cached before they were saved hasn't
+            match self.cached_puzzle(year, day).await? {
+                Some(puzzle) => self.save_examples(&puzzle).await?,
+                None => {
+                    self.puzzle(year, day, true).await?;
+                },
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Fetch a cached puzzle again if it only has part one, as once that's solved the page has
+    /// part two too
+    pub async fn refresh_puzzle(&self, year: usize, day: usize) -> Result<()> {
+        if !self.input.puzzle_path(year, day).exists() {
+            return Ok(());
+        }
+
+        if self.puzzle(year, day, false).await?.parts.len() < 2 {
+            self.puzzle(year, day, true).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Download input for every selected day that doesn't have it yet. Days which haven't
+    /// unlocked are an error, unless `wait` is set, in which case this waits until they do.
+    pub async fn download_missing(
+        &self,
+        selection: &[Challenge],
+        wait: bool,
+    ) -> Result<Vec<Downloaded>> {
         let missing_days = selection
             .iter()
-            .map(|challenge| challenge.day)
+            .map(|challenge| (challenge.year, challenge.day))
+            .sorted()
             .dedup()
-            .filter(|day| !self.input.has_input(*day))
+            .filter(|&(year, day)| calendar::unlock_time(year, day).is_some())
+            .filter(|&(year, day)| !self.has_input(year, day))
             .collect_vec();
 
+        let now = Utc::now();
+        let last_locked = missing_days
+            .iter()
+            .filter(|&&(year, day)| !calendar::is_unlocked(year, day, now))
+            .max();
+        if let Some(&(year, day)) = last_locked {
+            let unlock = calendar::unlock_time(year, day).ok_or(Error::NotUnlocked(year, day))?;
+            if !wait {
+                return Err(Error::LockedUntil(year, day, unlock.with_timezone(&Local)));
+            }
+
+            wait_for_unlock(year, day, unlock).await;
+        }
+
+        self.download_days(missing_days).await
+    }
+
+    /// Download input for `days` again, replacing what's saved, as when it's been damaged
+    pub async fn redownload(&self, days: &[(usize, usize)]) -> Result<Vec<Downloaded>> {
+        self.download_days(days.to_vec()).await
+    }
+
+    /// Download every day at once, recording the ones saved in the manifest even if another
+    /// failed, as their inputs are on disk either way
+    async fn download_days(&self, days: Vec<(usize, usize)>) -> Result<Vec<Downloaded>> {
         let mut join_set = JoinSet::new();
-        for day in missing_days {
-            let dl = InputDownload::new(self, day);
+        for (year, day) in days {
+            let dl = InputDownload::new(self, year, day);
             join_set.spawn(dl.run());
         }
 
-        join_set
-            .join_all()
-            .await
+        let (saved, failed): (Vec<_>, Vec<_>) =
+            join_set.join_all().await.into_iter().partition_result();
+
+        if !saved.is_empty() {
+            let mut manifest = Manifest::load(self.input.manifest_path()).await?;
+            for (download, entry) in &saved {
+                manifest.insert(download.year, download.day, entry.clone());
+            }
+            manifest.save().await?;
+        }
+        if let Some(err) = failed.into_iter().next() {
+            return Err(err);
+        }
+
+        let mut downloaded = saved
             .into_iter()
-            .collect::<Result<Vec<()>>>()?;
+            .map(|(download, _)| download)
+            .collect_vec();
+        downloaded.sort_by_key(|download| (download.year, download.day));
 
-        Ok(())
+        Ok(downloaded)
     }
 }
 
 struct InputDownload {
+    year: usize,
     day: usize,
     filepath: PathBuf,
-    session: String,
-    client: reqwest::Client,
+    cookie: String,
+    client: Client,
+    retry: RetryPolicy,
+    key: Option<Key>,
 }
 
 impl InputDownload {
-    fn new(parent: &Download, day: usize) -> Self {
+    fn new(parent: &Download, year: usize, day: usize) -> Self {
+        let filepath = match parent.key {
+            Some(_) => parent.input.encrypted_path(year, day),
+            None => parent.input.filepath(year, day),
+        };
+
         Self {
+            year,
             day,
-            filepath: parent.input.filepath(day),
-            session: parent.session.clone(),
+            filepath,
+            cookie: parent.session.cookie(),
             client: parent.client.clone(),
+            retry: parent.retry.clone(),
+            key: parent.key.clone(),
         }
     }
 
-    async fn run(self) -> Result<()> {
-        let mut url = Url::parse(BASE_URI)?;
-        url.path_segments_mut()?
-            .push("day")
-            .push(&self.day.to_string())
-            .push("input");
+    async fn run(self) -> Result<(Downloaded, ManifestEntry)> {
+        let url = self.client.url(&[
+            &self.year.to_string(),
+            "day",
+            &self.day.to_string(),
+            "input",
+        ])?;
 
         let req = self
             .client
             .get(url)
-            .header("Cookie", format!("session={}", self.session))
+            .header("Cookie", &self.cookie)
             .build()?;
 
-        let res = self.client.execute(req).await?.bytes().await?;
+        let (res, attempts) = self.client.execute_with_retry(req, &self.retry).await;
+        let res = res
+            .and_then(|res| validate(self.year, self.day, res.status, &res.body).map(|()| res))
+            .map_err(|err| match attempts {
+                1 => err,
+                _ => Error::RetriesExhausted(attempts, Box::new(err)),
+            })?;
 
-        dbg!(&self.filepath);
-        fs::write(self.filepath, &res).await?;
+        match &self.key {
+            Some(key) => {
+                let encrypted = key.encrypt(self.year, self.day, &res.body);
+                write_atomic(&self.filepath, &encrypted).await?
+            },
+            None => write_atomic(&self.filepath, &res.body).await?,
+        }
 
-        Ok(())
+        let downloaded = Downloaded {
+            year: self.year,
+            day: self.day,
+            attempts,
+        };
+        Ok((downloaded, ManifestEntry::new(&res.body, Local::now())))
+    }
+}
+
+/// Count down to `unlock` on stderr, returning once it has passed
+async fn wait_for_unlock(year: usize, day: usize, unlock: DateTime<Utc>) {
+    // Our clock and the server's rarely agree to the second, so don't ask the moment it unlocks
+    let unlock = unlock + chrono::Duration::seconds(1);
+    let live = std::io::stderr().is_terminal();
+
+    if !live {
+        eprintln!(
+            "waiting for {} day {} to unlock at {}",
+            year,
+            day,
+            unlock.with_timezone(&Local)
+        );
+    }
+
+    while let Ok(remaining) = (unlock - Utc::now()).to_std() {
+        if remaining.is_zero() {
+            break;
+        }
+
+        if live {
+            let secs = remaining.as_secs();
+            eprint!(
+                "\r\x1b[2K{} day {} unlocks in {:02}:{:02}:{:02}",
+                year,
+                day,
+                secs / 3600,
+                secs / 60 % 60,
+                secs % 60
+            );
+        }
+
+        tokio::time::sleep(remaining.min(Duration::from_secs(1))).await;
+    }
+
+    if live {
+        eprintln!();
+    }
+}
+
+/// Check that a response holds puzzle input, rather than an error page served in its place
+fn validate(year: usize, day: usize, status: StatusCode, body: &[u8]) -> Result<()> {
+    let text = String::from_utf8_lossy(body);
+
+    match status {
+        StatusCode::BAD_REQUEST if text.contains("Please log in") => Err(Error::SessionExpired),
+        StatusCode::NOT_FOUND => Err(Error::NotUnlocked(year, day)),
+        status if status.is_server_error() => Err(Error::ServerError(status)),
+        StatusCode::OK => {
+            if text.trim().is_empty() {
+                Err(Error::UnrecognizedResponse("empty input".into()))
+            } else if manifest::is_html(body) {
+                Err(Error::UnrecognizedResponse(
+                    "html page instead of input".into(),
+                ))
+            } else if std::str::from_utf8(body).is_err() {
+                Err(Error::UnrecognizedResponse("input is not utf8".into()))
+            } else {
+                Ok(())
+            }
+        },
+        status => Err(Error::UnrecognizedResponse(format!(
+            "{}: {}",
+            status,
+            text.trim()
+        ))),
+    }
+}
+
+/// Write to a temporary file next to `path` and rename it into place, so an interrupted download
+/// never leaves a partial input behind
+pub(super) async fn write_atomic(path: &Path, contents: &[u8]) -> Result<()> {
+    let mut temp_name = path.file_name().ok_or(Error::PathError)?.to_os_string();
+    temp_name.push(".download");
+    let temp = path.with_file_name(temp_name);
+
+    let result = async {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).await?;
+        }
+        fs::write(&temp, contents).await?;
+        fs::rename(&temp, path).await
+    }
+    .await;
+
+    if result.is_err() {
+        let _ = fs::remove_file(&temp).await;
+    }
+
+    Ok(result?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::input::mock::{MockServer, Reply, RunningServer, SESSION};
+    use crate::input::session::SessionSource;
+    use crate::select::ChallengePart;
+
+    /// A directory for the test called `name` alone, as tests run in parallel and clean up after
+    /// themselves
+    fn temp_dir(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("aoc24-{}-{}", name, std::process::id()))
+    }
+
+    fn download(server: &RunningServer, dir: &Path) -> Download {
+        let session = Session::new(SESSION, SessionSource::Flag).unwrap();
+        let mut download = Download::new(&InputFiles::new(dir).unwrap(), session, server.client());
+        download.retry.base_delay = Duration::from_millis(1);
+        download
+    }
+
+    fn day(day: usize) -> Vec<Challenge> {
+        vec![Challenge::new(2024, day, ChallengePart::First)]
+    }
+
+    fn report(year: usize, day: usize, attempts: usize) -> Downloaded {
+        Downloaded {
+            year,
+            day,
+            attempts,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_download() {
+        let server = MockServer::advent()
+            .route("GET", "/2023/day/1/input", Reply::ok("1abc2\n"))
+            .start()
+            .await;
+        let dir = temp_dir("download");
+        let download = download(&server, &dir);
+
+        let mut selection = day(1);
+        selection.push(Challenge::new(2023, 1, ChallengePart::Second));
+        let downloaded = download.download_missing(&selection, false).await.unwrap();
+        let written = fs::read_to_string(dir.join("2024").join("day1"))
+            .await
+            .unwrap();
+        let written_2023 = fs::read_to_string(dir.join("2023").join("day1"))
+            .await
+            .unwrap();
+
+        let manifest = Manifest::load(dir.join("manifest")).await.unwrap();
+
+        // Inputs already on disk aren't downloaded again
+        let again = download.download_missing(&selection, false).await.unwrap();
+        fs::remove_dir_all(&dir).await.unwrap();
+
+        assert_eq!(downloaded, vec![report(2023, 1, 1), report(2024, 1, 1)]);
+        assert_eq!(written, include_str!("../../fixtures/mock/day1_input"));
+        assert_eq!(written_2023, "1abc2\n");
+        assert_eq!(
+            manifest.get(2023, 1).map(|entry| (entry.size, entry.lines)),
+            Some((6, 1))
+        );
+        assert!(manifest::check(written.as_bytes(), manifest.get(2024, 1)).is_none());
+        assert!(again.is_empty());
+
+        let requests = server.requests();
+        assert_eq!(requests.len(), 2);
+        assert!(requests
+            .iter()
+            .any(|request| request.path == "/2023/day/1/input"));
+        assert_eq!(requests[0].header("cookie"), requests[1].header("cookie"));
+        assert_eq!(
+            requests[0].header("cookie"),
+            Some(format!("session={}", SESSION).as_str())
+        );
+    }
+
+    #[tokio::test]
+    async fn test_download_encrypted() {
+        let server = MockServer::advent().start().await;
+        let dir = temp_dir("download-encrypted");
+        let key = Key::generate();
+        let download = download(&server, &dir).with_key(Some(key.clone()));
+
+        let downloaded = download.download_missing(&day(1), false).await.unwrap();
+        let written = fs::read(dir.join("2024").join("day1.enc")).await.unwrap();
+        let plain = fs::try_exists(dir.join("2024").join("day1")).await.unwrap();
+        let again = download.download_missing(&day(1), false).await.unwrap();
+        fs::remove_dir_all(&dir).await.unwrap();
+
+        assert_eq!(downloaded, vec![report(2024, 1, 1)]);
+        assert_eq!(
+            key.decrypt(2024, 1, &written).unwrap(),
+            include_bytes!("../../fixtures/mock/day1_input")
+        );
+        assert!(!plain, "only the encrypted input is written");
+        assert!(again.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_download_retries() {
+        let server = MockServer::new()
+            .route("GET", "/2024/day/2/input", Reply::server_error())
+            .route("GET", "/2024/day/2/input", Reply::ok("7 6 4 2 1\n"))
+            .route("GET", "/2024/day/3/input", Reply::server_error())
+            .start()
+            .await;
+        let dir = temp_dir("download-retries");
+        let download = download(&server, &dir);
+
+        let downloaded = download.download_missing(&day(2), false).await;
+        let failed = download.download_missing(&day(3), false).await;
+        fs::remove_dir_all(&dir).await.unwrap();
+
+        assert_eq!(downloaded.unwrap(), vec![report(2024, 2, 2)]);
+        assert!(matches!(
+            failed,
+            Err(Error::RetriesExhausted(4, err)) if matches!(*err, Error::ServerError(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_download_errors() {
+        let server = MockServer::new()
+            .route("GET", "/2024/day/4/input", Reply::login_required())
+            .route("GET", "/2024/day/5/input", Reply::not_unlocked())
+            .start()
+            .await;
+        let dir = temp_dir("download-errors");
+        let download = download(&server, &dir);
+
+        let expired = download.download_missing(&day(4), false).await;
+        let locked = download.download_missing(&day(5), false).await;
+        let leftovers = std::fs::read_dir(&dir).unwrap().count();
+        fs::remove_dir_all(&dir).await.unwrap();
+
+        assert!(matches!(expired, Err(Error::SessionExpired)));
+        assert!(matches!(locked, Err(Error::NotUnlocked(2024, 5))));
+        assert_eq!(leftovers, 0);
+        // Client errors aren't retried
+        assert_eq!(server.requests().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_puzzle() {
+        let part_two = include_str!("../../fixtures/mock/day1.html").replace(
+            "</article>",
+            "</article><article class=\"day-desc\"><h2 id=\"part2\">--- Part Two ---</h2>\
+             <p>This time, figure out <em>how often</em> each number appears.</p></article>",
+        );
+        let server = MockServer::advent()
+            .route("GET", "/2024/day/1", Reply::ok(part_two))
+            .start()
+            .await;
+        let dir = temp_dir("puzzle");
+        let download = download(&server, &dir);
+
+        let fetched = download.puzzle(2024, 1, false).await.unwrap();
+        let cached = download.puzzle(2024, 1, false).await.unwrap();
+        download.refresh_puzzle(2024, 1).await.unwrap();
+        let refreshed = download.puzzle(2024, 1, false).await.unwrap();
+        let not_unlocked = download.puzzle(2024, 2, false).await;
+        fs::remove_dir_all(&dir).await.unwrap();
+
+        assert_eq!(fetched.parts.len(), 1);
+        assert_eq!(cached, fetched);
+        assert_eq!(refreshed.parts.len(), 2);
+        assert_eq!(
+            refreshed.parts[1],
+            "## --- Part Two ---\n\nThis time, figure out *how often* each number appears."
+        );
+        assert!(matches!(not_unlocked, Err(Error::NotUnlocked(2024, 2))));
+        assert_eq!(server.requests().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_examples() {
+        let server = MockServer::advent()
+            .route(
+                "GET",
+                "/2023/day/1",
+                Reply::ok(include_str!("../../fixtures/mock/day1.html")),
+            )
+            .start()
+            .await;
+        let dir = temp_dir("examples");
+        let download = download(&server, &dir);
+
+        // Each day is fetched once, however its parts are spread through the selection
+        let mut selection = day(1);
+        selection.push(Challenge::new(2023, 1, ChallengePart::First));
+        selection.push(Challenge::new(2024, 1, ChallengePart::Second));
+        download.download_examples(&selection, 1).await.unwrap();
+        let input = InputFiles::new(&dir).unwrap().with_example(Some(1));
+        let example = input.get_input(2024, 1);
+        let missing = input.with_example(Some(2)).get_input(2024, 1);
+        fs::remove_dir_all(&dir).await.unwrap();
+
+        assert_eq!(
+            &*example.unwrap(),
+            include_str!("../../fixtures/mock/day1_input")
+        );
+        assert!(matches!(missing, Err(Error::NoExample(1, 2))));
+        assert_eq!(server.requests().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_check_session() {
+        let server = MockServer::advent().start().await;
+        let dir = temp_dir("check-session");
+        let user = download(&server, &dir).check_session(2024).await;
+        fs::remove_dir_all(&dir).await.unwrap();
+
+        assert_eq!(user.unwrap(), "Test User");
+    }
+
+    #[test]
+    fn test_validate() {
+        let ok = validate(2024, 1, StatusCode::OK, b"3   4\n4   3\n");
+        assert!(ok.is_ok());
+
+        let login = b"Puzzle inputs differ by user.  Please log in to get your puzzle input.\n";
+        assert!(matches!(
+            validate(2024, 1, StatusCode::BAD_REQUEST, login),
+            Err(Error::SessionExpired)
+        ));
+
+        let locked = b"Please don't repeatedly request this endpoint before it unlocks!";
+        assert!(matches!(
+            validate(2024, 25, StatusCode::NOT_FOUND, locked),
+            Err(Error::NotUnlocked(2024, 25))
+        ));
+
+        assert!(matches!(
+            validate(2024, 1, StatusCode::BAD_GATEWAY, b"<html>502</html>"),
+            Err(Error::ServerError(StatusCode::BAD_GATEWAY))
+        ));
+
+        for body in [
+            &b""[..],
+            b"\n",
+            b"<!DOCTYPE html>\n<html></html>",
+            b"\xff\xfe",
+        ] {
+            assert!(matches!(
+                validate(2024, 1, StatusCode::OK, body),
+                Err(Error::UnrecognizedResponse(_))
+            ));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_write_atomic() {
+        let dir = temp_dir("write-atomic");
+        fs::create_dir_all(&dir).await.unwrap();
+        let path = dir.join("day1");
+
+        write_atomic(&path, b"1 2\n").await.unwrap();
+        let written = fs::read_to_string(&path).await.unwrap();
+        let leftovers = std::fs::read_dir(&dir).unwrap().count();
+        fs::remove_dir_all(&dir).await.unwrap();
+
+        assert_eq!(written, "1 2\n");
+        assert_eq!(leftovers, 1);
     }
 }
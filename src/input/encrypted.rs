@@ -0,0 +1,261 @@
+use std::fmt;
+use std::io::{BufRead, ErrorKind};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use chacha20poly1305::aead::{Aead, AeadCore, KeyInit, OsRng, Payload};
+use chacha20poly1305::{ChaCha20Poly1305, Nonce};
+use tokio::fs;
+
+use crate::input::download::write_atomic;
+use crate::input::input_files::{cached, Cache};
+use crate::input::{Error, Input, InputFiles, Result};
+
+/// Where the key is kept unless given another path, which must never be committed
+pub const KEY_FILE: &str = ".input-key";
+
+/// Bytes of nonce at the start of every encrypted file
+const NONCE_LEN: usize = 12;
+
+/// Symmetric key for inputs stored encrypted, so they can be shared through git without
+/// publishing them
+#[derive(Clone)]
+pub struct Key(chacha20poly1305::Key);
+
+impl Key {
+    pub fn generate() -> Self {
+        Self(ChaCha20Poly1305::generate_key(&mut OsRng))
+    }
+
+    /// Read a key saved by [`Key::save`], or `None` if there's no file at `path`
+    pub async fn load(path: &Path) -> Result<Option<Self>> {
+        let text = match fs::read_to_string(path).await {
+            Ok(text) => text,
+            Err(err) if err.kind() == ErrorKind::NotFound => return Ok(None),
+            Err(err) => return Err(err.into()),
+        };
+
+        let text = text.trim();
+        let bytes = (0..text.len())
+            .step_by(2)
+            .map(|i| {
+                text.get(i..i + 2)
+                    .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+            })
+            .collect::<Option<Vec<u8>>>()
+            .filter(|bytes| bytes.len() == 32)
+            .ok_or_else(|| Error::InvalidKey(path.to_path_buf()))?;
+
+        Ok(Some(Self(*chacha20poly1305::Key::from_slice(&bytes))))
+    }
+
+    /// Write the key as hex to a new file at `path`, readable only by its owner
+    pub async fn save(&self, path: &Path) -> Result<()> {
+        let hex: String = self.0.iter().map(|b| format!("{:02x}", b)).collect();
+
+        let mut options = fs::OpenOptions::new();
+        options.write(true).create_new(true);
+        #[cfg(unix)]
+        options.mode(0o600);
+
+        let mut file = options.open(path).await?;
+        tokio::io::AsyncWriteExt::write_all(&mut file, format!("{}\n", hex).as_bytes()).await?;
+
+        Ok(())
+    }
+
+    /// Encrypt a day's input, tied to that day so it can't be passed off as another's
+    pub fn encrypt(&self, year: usize, day: usize, input: &[u8]) -> Vec<u8> {
+        let nonce = ChaCha20Poly1305::generate_nonce(&mut OsRng);
+        let aad = associated_data(year, day);
+        let payload = Payload {
+            msg: input,
+            aad: &aad,
+        };
+
+        let mut encrypted = nonce.to_vec();
+        encrypted.extend(
+            ChaCha20Poly1305::new(&self.0)
+                .encrypt(&nonce, payload)
+                .expect("encrypting in memory can't fail"),
+        );
+        encrypted
+    }
+
+    /// Decrypt a day's input, or `None` if it wasn't encrypted with this key for that day
+    pub fn decrypt(&self, year: usize, day: usize, encrypted: &[u8]) -> Option<Vec<u8>> {
+        if encrypted.len() < NONCE_LEN {
+            return None;
+        }
+
+        let (nonce, msg) = encrypted.split_at(NONCE_LEN);
+        let aad = associated_data(year, day);
+        ChaCha20Poly1305::new(&self.0)
+            .decrypt(Nonce::from_slice(nonce), Payload { msg, aad: &aad })
+            .ok()
+    }
+}
+
+fn associated_data(year: usize, day: usize) -> Vec<u8> {
+    format!("aoc24 {} day {}", year, day).into_bytes()
+}
+
+impl fmt::Debug for Key {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Key").field(&"<redacted>").finish()
+    }
+}
+
+/// Inputs kept encrypted next to where the plain ones would be, as `input/<year>/day<day>.enc`.
+/// Days without an encrypted input fall back to the plain one, as do examples, which are public.
+#[derive(Debug, Clone)]
+pub struct EncryptedInput {
+    files: InputFiles,
+    key: Key,
+    text: Cache<str>,
+    bytes: Cache<[u8]>,
+}
+
+impl EncryptedInput {
+    pub fn new(files: InputFiles, key: Key) -> Self {
+        Self {
+            files,
+            key,
+            text: Cache::default(),
+            bytes: Cache::default(),
+        }
+    }
+
+    /// The encrypted input to read for `day`, if there is one to use
+    fn encrypted(&self, year: usize, day: usize) -> Option<PathBuf> {
+        let path = self.files.encrypted_path(year, day);
+        (self.files.example().is_none() && path.exists()).then_some(path)
+    }
+
+    fn decrypt_file(&self, year: usize, day: usize, path: &Path) -> Result<Vec<u8>> {
+        let encrypted = std::fs::read(path)?;
+        self.key
+            .decrypt(year, day, &encrypted)
+            .ok_or_else(|| Error::DecryptError(path.to_path_buf()))
+    }
+
+    /// Save an encrypted copy of the plain input for `day`, returning whether anything changed. An
+    /// existing copy is left alone if it already holds the same input, as encrypting it again
+    /// would only churn the file.
+    pub async fn encrypt(&self, year: usize, day: usize) -> Result<bool> {
+        let input = fs::read(self.files.filepath(year, day)).await?;
+        let path = self.files.encrypted_path(year, day);
+        if path.exists() && self.decrypt_file(year, day, &path)? == input {
+            return Ok(false);
+        }
+
+        write_atomic(&path, &self.key.encrypt(year, day, &input)).await?;
+        Ok(true)
+    }
+
+    /// Save the plain input for `day` from its encrypted copy, returning whether anything changed
+    pub async fn decrypt(&self, year: usize, day: usize) -> Result<bool> {
+        let input = self.decrypt_file(year, day, &self.files.encrypted_path(year, day))?;
+        let path = self.files.filepath(year, day);
+        if fs::read(&path).await.is_ok_and(|plain| plain == input) {
+            return Ok(false);
+        }
+
+        write_atomic(&path, &input).await?;
+        Ok(true)
+    }
+}
+
+impl Input for EncryptedInput {
+    fn has_input(&self, year: usize, day: usize) -> bool {
+        self.encrypted(year, day).is_some() || self.files.has_input(year, day)
+    }
+
+    fn get_input(&self, year: usize, day: usize) -> Result<Arc<str>> {
+        let Some(path) = self.encrypted(year, day) else {
+            return self.files.get_input(year, day);
+        };
+
+        cached(&self.text, path, |path| {
+            let text = String::from_utf8(self.decrypt_file(year, day, path)?)
+                .map_err(|_| Error::Utf8Error(year, day))?;
+            Ok(text.into())
+        })
+    }
+
+    fn get_bytes(&self, year: usize, day: usize) -> Result<Arc<[u8]>> {
+        let Some(path) = self.encrypted(year, day) else {
+            return self.files.get_bytes(year, day);
+        };
+
+        cached(&self.bytes, path, |path| {
+            Ok(self.decrypt_file(year, day, path)?.into())
+        })
+    }
+
+    fn reader(&self, year: usize, day: usize) -> Result<Box<dyn BufRead + Send>> {
+        match self.encrypted(year, day) {
+            Some(_) => Ok(Box::new(std::io::Cursor::new(self.get_bytes(year, day)?))),
+            None => self.files.reader(year, day),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encrypt() {
+        let key = Key::generate();
+        let encrypted = key.encrypt(2024, 1, b"3   4\n");
+
+        assert_eq!(key.decrypt(2024, 1, &encrypted).unwrap(), b"3   4\n");
+        assert_ne!(&encrypted[NONCE_LEN..], b"3   4\n");
+        assert!(
+            key.decrypt(2024, 2, &encrypted).is_none(),
+            "tied to its day"
+        );
+        assert!(Key::generate().decrypt(2024, 1, &encrypted).is_none());
+        assert!(key.decrypt(2024, 1, &encrypted[..8]).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_encrypted_input() {
+        let dir = std::env::temp_dir().join(format!("aoc24-encrypted-{}", std::process::id()));
+        let files = InputFiles::new(&dir).unwrap();
+        let key_path = dir.join(KEY_FILE);
+        Key::generate().save(&key_path).await.unwrap();
+        let key = Key::load(&key_path).await.unwrap().unwrap();
+        let not_saved_again = Key::generate().save(&key_path).await;
+
+        fs::create_dir_all(dir.join("2024")).await.unwrap();
+        fs::write(files.filepath(2024, 1), "3   4\n").await.unwrap();
+        let input = EncryptedInput::new(files.clone(), key.clone());
+        let encrypted = input.encrypt(2024, 1).await.unwrap();
+        let unchanged = fs::read(files.encrypted_path(2024, 1)).await.unwrap();
+        let encrypted_again = input.encrypt(2024, 1).await.unwrap();
+        let reencrypted = fs::read(files.encrypted_path(2024, 1)).await.unwrap();
+
+        // A fresh checkout, with only the encrypted input
+        fs::remove_file(files.filepath(2024, 1)).await.unwrap();
+        let fresh = EncryptedInput::new(InputFiles::new(&dir).unwrap(), key.clone());
+        let text = fresh.get_input(2024, 1);
+        let decrypted = fresh.decrypt(2024, 1).await.unwrap();
+        let plain = fs::read_to_string(files.filepath(2024, 1)).await.unwrap();
+        let wrong_key = EncryptedInput::new(files.clone(), Key::generate()).get_bytes(2024, 1);
+        fs::remove_dir_all(&dir).await.unwrap();
+
+        assert!(matches!(not_saved_again, Err(Error::IOError(_))));
+        assert!(encrypted);
+        assert!(!encrypted_again);
+        assert_eq!(
+            unchanged, reencrypted,
+            "unchanged inputs aren't encrypted again"
+        );
+        assert_eq!(&*text.unwrap(), "3   4\n");
+        assert!(decrypted);
+        assert_eq!(plain, "3   4\n");
+        assert!(matches!(wrong_key, Err(Error::DecryptError(_))));
+    }
+}
@@ -4,10 +4,17 @@ use thiserror::Error;
 
 #[derive(Error, Debug)]
 pub enum Error {
-    #[error("no session file: place advent of code session key in ./.session")]
-    NoSessionFile,
+    #[error(
+        "no advent of code session: use --session, set AOC_SESSION, or put it in .session or the \
+         user config directory"
+    )]
+    NoSession,
     #[error("input file {0} does not exist")]
     NoInputFile(PathBuf),
+    #[error("input set directory {0} does not exist")]
+    NoInputSet(PathBuf),
+    #[error("day {0} puzzle has no example {1}")]
+    NoExample(usize, usize),
     #[error("http error: {0}")]
     HttpError(#[from] reqwest::Error),
     #[error("path error")]
@@ -22,8 +29,30 @@ pub enum Error {
     PoisonedInputLockError,
     #[error("line parse error: {0}")]
     LineParseError(String),
-    #[error("file `{0}` is not utf8")]
-    Utf8Error(PathBuf),
+    #[error("{0} day {1} input is not utf8")]
+    Utf8Error(usize, usize),
+    #[error("session cookie expired or invalid: log in to advent of code again and update it")]
+    SessionExpired,
+    #[error("{0} day {1} is not unlocked yet")]
+    NotUnlocked(usize, usize),
+    #[error("{0} day {1} unlocks at {2}, use --wait to download it then")]
+    LockedUntil(usize, usize, chrono::DateTime<chrono::Local>),
+    #[error("no input key in {0}, create one with `aoc24 encrypt` or ask whoever did for it")]
+    NoKey(PathBuf),
+    #[error("key file {0} should hold a 64 digit hex key")]
+    InvalidKey(PathBuf),
+    #[error("couldn't decrypt {0}, it was encrypted with another key or has been changed")]
+    DecryptError(PathBuf),
+    #[error("advent of code server error: {0}")]
+    ServerError(reqwest::StatusCode),
+    #[error("failed after {0} attempts: {1}")]
+    RetriesExhausted(usize, Box<Error>),
+    #[error("unrecognized response from advent of code: {0}")]
+    UnrecognizedResponse(String),
+    #[error("submission log error: {0}")]
+    SubmissionLogError(String),
+    #[error("input manifest error: {0}")]
+    ManifestError(String),
 }
 
 impl From<()> for Error {
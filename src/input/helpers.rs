@@ -1,23 +1,39 @@
-use std::sync::{Arc, Mutex};
+use std::io::BufRead;
+use std::sync::Arc;
 
 use winnow::error::ContextError;
 use winnow::prelude::*;
 
-use crate::input::{Input, Result};
+use crate::input::{Input, Lines, Result};
 
+#[derive(Debug)]
 pub struct InputHelper {
+    year: usize,
     day: usize,
-    input: Arc<Mutex<dyn Input>>,
+    input: Arc<dyn Input>,
 }
 
 impl InputHelper {
-    pub fn new(day: usize, input: Arc<Mutex<dyn Input>>) -> Self {
-        Self { day, input }
+    pub fn new(year: usize, day: usize, input: Arc<dyn Input>) -> Self {
+        Self { year, day, input }
     }
 
-    pub fn all_text(&self) -> Result<String> {
-        let mut input = self.input.lock()?;
-        input.get_input(self.day)
+    pub fn all_text(&self) -> Result<Arc<str>> {
+        self.input.get_input(self.year, self.day)
+    }
+
+    /// The input without checking it's UTF-8, for days which parse it byte by byte
+    pub fn all_bytes(&self) -> Result<Arc<[u8]>> {
+        self.input.get_bytes(self.year, self.day)
+    }
+
+    /// Each line of the input as it's read, for days which parse a line at a time
+    pub fn lines(&self) -> Result<Lines> {
+        Ok(Lines::new(self.year, self.day, self.reader()?))
+    }
+
+    pub fn reader(&self) -> Result<Box<dyn BufRead + Send>> {
+        self.input.reader(self.year, self.day)
     }
 }
 
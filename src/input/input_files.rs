@@ -1,12 +1,32 @@
+use std::collections::HashMap;
 use std::fs::{self, File};
-use std::io::Read;
+use std::io::{BufRead, BufReader, ErrorKind};
 use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock};
 
-use crate::input::{Input, Result};
+use crate::input::{Error, Input, Result};
+
+/// The only year inputs were saved for before they were kept under a directory per year
+const LEGACY_YEAR: usize = 2024;
+
+/// Under the input directory, holding a directory for each named input set
+const SETS_DIR: &str = "sets";
+
+/// In each input set's directory, the answers accepted for that set's inputs
+const SET_ANSWERS_FILE: &str = "answers";
+
+/// Records what each input was when downloaded, see [`Manifest`](crate::input::Manifest)
+const MANIFEST_FILE: &str = "manifest";
+
+/// Files already read, by path. Clones share it, so each file is read at most once.
+pub(super) type Cache<T> = Arc<RwLock<HashMap<PathBuf, Arc<T>>>>;
 
 #[derive(Debug, Clone)]
 pub struct InputFiles {
     base_path: PathBuf,
+    example: Option<usize>,
+    text: Cache<str>,
+    bytes: Cache<[u8]>,
 }
 
 impl InputFiles {
@@ -14,24 +34,341 @@ impl InputFiles {
         let base_path = base_path.as_ref().to_path_buf();
         fs::create_dir_all(&base_path)?;
 
-        Ok(Self { base_path })
+        Ok(Self {
+            base_path,
+            example: None,
+            text: Cache::default(),
+            bytes: Cache::default(),
+        })
+    }
+
+    /// Read the numbered example from each day's puzzle instead of the real input
+    pub fn with_example(mut self, example: Option<usize>) -> Self {
+        self.example = example;
+        self
+    }
+
+    pub fn example(&self) -> Option<usize> {
+        self.example
+    }
+
+    fn year_path(&self, year: usize) -> PathBuf {
+        self.base_path.join(year.to_string())
+    }
+
+    /// Where the input for `day` of `year` is stored, `input/<year>/day<day>`. Inputs saved
+    /// before years were recorded, straight under `input/`, are still found for 2024.
+    pub fn filepath(&self, year: usize, day: usize) -> PathBuf {
+        let path = self.year_path(year).join(format!("day{day}"));
+        let legacy = self.base_path.join(format!("day{day}"));
+
+        if year == LEGACY_YEAR && !path.exists() && legacy.exists() {
+            legacy
+        } else {
+            path
+        }
+    }
+
+    /// Where the input for `day` of `year` is stored encrypted, see
+    /// [`EncryptedInput`](crate::input::EncryptedInput)
+    pub fn encrypted_path(&self, year: usize, day: usize) -> PathBuf {
+        self.year_path(year).join(format!("day{day}.enc"))
     }
 
-    pub fn filepath(&self, day: usize) -> PathBuf {
-        self.base_path.join(format!("day{day}"))
+    pub fn manifest_path(&self) -> PathBuf {
+        self.base_path.join(MANIFEST_FILE)
+    }
+
+    /// Every `(year, day)` with an input saved, plain or encrypted, in order
+    pub fn saved_days(&self) -> Result<Vec<(usize, usize)>> {
+        let mut days = list_days(&self.base_path, LEGACY_YEAR)?;
+        for entry in fs::read_dir(&self.base_path)? {
+            let entry = entry?;
+            let year = entry
+                .file_name()
+                .to_str()
+                .and_then(|name| name.parse().ok());
+            if let (Some(year), true) = (year, entry.file_type()?.is_dir()) {
+                days.extend(list_days(&entry.path(), year)?);
+            }
+        }
+
+        days.sort();
+        days.dedup();
+        Ok(days)
+    }
+
+    /// Where the puzzle description for `day` is cached, as Markdown
+    pub fn puzzle_path(&self, year: usize, day: usize) -> PathBuf {
+        self.year_path(year).join(format!("day{day}.md"))
+    }
+
+    /// Where the `example`th code block from the puzzle for `day` is saved, counting from 1
+    pub fn example_path(&self, year: usize, day: usize, example: usize) -> PathBuf {
+        self.year_path(year)
+            .join(format!("day{day}.example{example}"))
+    }
+
+    /// Every named input set, in order of name
+    pub fn sets(&self) -> Result<Vec<InputSet>> {
+        let entries = match fs::read_dir(self.base_path.join(SETS_DIR)) {
+            Ok(entries) => entries,
+            Err(err) if err.kind() == ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(err) => return Err(err.into()),
+        };
+
+        let mut sets = Vec::new();
+        for entry in entries {
+            let entry = entry?;
+            if !entry.file_type()?.is_dir() {
+                continue;
+            }
+
+            let name = entry
+                .file_name()
+                .into_string()
+                .map_err(|_| Error::PathError)?;
+            sets.push(self.set(&name)?);
+        }
+        sets.sort_by(|a, b| a.name.cmp(&b.name));
+
+        Ok(sets)
+    }
+
+    /// The input set called `name`, which must already exist
+    pub fn set(&self, name: &str) -> Result<InputSet> {
+        let path = self.base_path.join(SETS_DIR).join(name);
+        if !path.is_dir() {
+            return Err(Error::NoInputSet(path));
+        }
+
+        Ok(InputSet {
+            name: name.to_string(),
+            input: Self::new(path)?,
+        })
+    }
+
+    fn selected_path(&self, year: usize, day: usize) -> PathBuf {
+        match self.example {
+            Some(example) => self.example_path(year, day, example),
+            None => self.filepath(year, day),
+        }
+    }
+
+    /// The path to read for `day`, checking a selected example was found in its puzzle
+    fn existing_path(&self, year: usize, day: usize) -> Result<PathBuf> {
+        if let Some(example) = self.example {
+            if !self.has_input(year, day) {
+                return Err(Error::NoExample(day, example));
+            }
+        }
+
+        Ok(self.selected_path(year, day))
+    }
+}
+
+/// The days with an input in `dir`, as `day<day>` or `day<day>.enc`
+fn list_days(dir: &Path, year: usize) -> Result<Vec<(usize, usize)>> {
+    let mut days = Vec::new();
+    for entry in fs::read_dir(dir)? {
+        let name = entry?.file_name();
+        let day = name
+            .to_str()
+            .and_then(|name| name.strip_prefix("day"))
+            .map(|name| name.strip_suffix(".enc").unwrap_or(name))
+            .and_then(|day| day.parse().ok());
+        if let Some(day) = day {
+            days.push((year, day));
+        }
+    }
+
+    Ok(days)
+}
+
+/// Whatever is in `cache` for `path`, or else what `load` gives for it
+pub(super) fn cached<T: ?Sized>(
+    cache: &Cache<T>,
+    path: PathBuf,
+    load: impl FnOnce(&Path) -> Result<Arc<T>>,
+) -> Result<Arc<T>> {
+    if let Some(loaded) = cache.read()?.get(&path) {
+        return Ok(loaded.clone());
+    }
+
+    // Load outside the lock, so other days aren't held up while this one does
+    let loaded = load(&path)?;
+    let mut cache = cache.write()?;
+    Ok(cache.entry(path).or_insert(loaded).clone())
+}
+
+/// Someone else's inputs, such as a teammate's, under `input/sets/<name>/` in the same layout as
+/// the main input directory. The answers accepted for them are kept alongside, in an `answers`
+/// file in the same format as the main one.
+#[derive(Debug, Clone)]
+pub struct InputSet {
+    pub name: String,
+    pub input: InputFiles,
+}
+
+impl InputSet {
+    pub fn answers_path(&self) -> PathBuf {
+        self.input.base_path.join(SET_ANSWERS_FILE)
     }
 }
 
 impl Input for InputFiles {
-    fn has_input(&self, day: usize) -> bool {
-        self.filepath(day).exists()
+    fn has_input(&self, year: usize, day: usize) -> bool {
+        self.selected_path(year, day).exists()
+    }
+
+    fn get_input(&self, year: usize, day: usize) -> Result<Arc<str>> {
+        cached(&self.text, self.existing_path(year, day)?, |path| {
+            let text =
+                String::from_utf8(fs::read(path)?).map_err(|_| Error::Utf8Error(year, day))?;
+            Ok(text.into())
+        })
+    }
+
+    fn get_bytes(&self, year: usize, day: usize) -> Result<Arc<[u8]>> {
+        cached(&self.bytes, self.existing_path(year, day)?, |path| {
+            Ok(fs::read(path)?.into())
+        })
+    }
+
+    /// Read straight from the file, leaving the cache alone
+    fn reader(&self, year: usize, day: usize) -> Result<Box<dyn BufRead + Send>> {
+        let file = File::open(self.existing_path(year, day)?)?;
+        Ok(Box::new(BufReader::new(file)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Read;
+
+    use super::*;
+    use crate::input::Lines;
+
+    #[test]
+    fn test_filepath() {
+        let dir = std::env::temp_dir().join(format!("aoc24-input-files-{}", std::process::id()));
+        let input = InputFiles::new(&dir).unwrap();
+        fs::write(dir.join("day1"), "legacy").unwrap();
+
+        let legacy = input.filepath(2024, 1);
+        let other_year = input.filepath(2023, 1);
+        let not_legacy = input.filepath(2024, 2);
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(legacy, dir.join("day1"));
+        assert_eq!(other_year, dir.join("2023").join("day1"));
+        assert_eq!(not_legacy, dir.join("2024").join("day2"));
     }
 
-    fn get_input(&mut self, day: usize) -> Result<String> {
-        let mut file = File::open(self.filepath(day))?;
-        let mut input = String::new();
-        file.read_to_string(&mut input)?;
+    #[test]
+    fn test_saved_days() {
+        let dir = std::env::temp_dir().join(format!("aoc24-saved-days-{}", std::process::id()));
+        let input = InputFiles::new(&dir).unwrap();
+        fs::create_dir_all(dir.join("2023")).unwrap();
+        fs::create_dir_all(dir.join("2024")).unwrap();
+        for path in [
+            "day3",
+            "2023/day25.enc",
+            "2023/day25",
+            "2023/day1.md",
+            "2024/day2.enc",
+        ] {
+            fs::write(dir.join(path), "").unwrap();
+        }
+
+        let days = input.saved_days().unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(days, [(2023, 25), (2024, 2), (2024, 3)]);
+    }
+
+    #[test]
+    fn test_cache() {
+        let dir = std::env::temp_dir().join(format!("aoc24-input-cache-{}", std::process::id()));
+        let input = InputFiles::new(&dir).unwrap();
+        fs::create_dir_all(dir.join("2024")).unwrap();
+        fs::write(dir.join("2024").join("day1"), "first").unwrap();
+
+        let first = input.get_input(2024, 1).unwrap();
+        fs::write(dir.join("2024").join("day1"), "changed").unwrap();
+        let again = input.clone().get_input(2024, 1).unwrap();
+        let fresh = InputFiles::new(&dir).unwrap().get_input(2024, 1).unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert!(Arc::ptr_eq(&first, &again), "clones share what's been read");
+        assert_eq!(&*fresh, "changed");
+    }
+
+    #[test]
+    fn test_bytes() {
+        let dir = std::env::temp_dir().join(format!("aoc24-input-bytes-{}", std::process::id()));
+        let input = InputFiles::new(&dir).unwrap();
+        fs::create_dir_all(dir.join("2024")).unwrap();
+        fs::write(dir.join("2024").join("day1"), "3   4\r\n4   3\n").unwrap();
+        fs::write(dir.join("2024").join("day2"), b"ok\n\xff\n").unwrap();
+
+        let bytes = input.get_bytes(2024, 1).unwrap();
+        let mut read = String::new();
+        input
+            .reader(2024, 1)
+            .unwrap()
+            .read_to_string(&mut read)
+            .unwrap();
+        let lines = Lines::new(2024, 1, input.reader(2024, 1).unwrap())
+            .collect::<Result<Vec<_>>>()
+            .unwrap();
+        let binary = input.get_bytes(2024, 2).unwrap();
+        let text = input.get_input(2024, 2);
+        let binary_lines = Lines::new(2024, 2, input.reader(2024, 2).unwrap()).collect::<Vec<_>>();
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(&*bytes, b"3   4\r\n4   3\n");
+        assert_eq!(read, "3   4\r\n4   3\n");
+        assert_eq!(lines, ["3   4", "4   3"]);
+        assert_eq!(&*binary, b"ok\n\xff\n");
+        assert!(matches!(text, Err(Error::Utf8Error(2024, 2))));
+        assert!(matches!(binary_lines[0], Ok(ref line) if line == "ok"));
+        assert!(matches!(binary_lines[1], Err(Error::Utf8Error(2024, 2))));
+    }
+
+    #[test]
+    fn test_sets() {
+        let dir = std::env::temp_dir().join(format!("aoc24-input-sets-{}", std::process::id()));
+        let input = InputFiles::new(&dir).unwrap();
+        let no_sets = input.sets().unwrap();
+
+        fs::create_dir_all(dir.join("sets").join("bob").join("2024")).unwrap();
+        fs::create_dir_all(dir.join("sets").join("alice")).unwrap();
+        fs::write(dir.join("sets").join("README"), "not a set").unwrap();
+        fs::write(
+            dir.join("sets").join("bob").join("2024").join("day1"),
+            "1 2",
+        )
+        .unwrap();
+
+        let sets = input.sets().unwrap();
+        let missing = input.set("carol");
+        let has_input = sets
+            .iter()
+            .map(|set| set.input.has_input(2024, 1))
+            .collect::<Vec<_>>();
+        fs::remove_dir_all(&dir).unwrap();
 
-        Ok(input)
+        assert!(no_sets.is_empty());
+        assert_eq!(
+            sets.iter().map(|set| set.name.as_str()).collect::<Vec<_>>(),
+            ["alice", "bob"]
+        );
+        assert_eq!(has_input, [false, true]);
+        assert_eq!(
+            sets[1].answers_path(),
+            dir.join("sets").join("bob").join("answers")
+        );
+        assert!(matches!(missing, Err(Error::NoInputSet(_))));
     }
 }
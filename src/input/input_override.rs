@@ -0,0 +1,149 @@
+use std::convert::Infallible;
+use std::fmt;
+use std::io::{BufRead, Cursor, ErrorKind};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::Arc;
+
+use tokio::io::AsyncReadExt;
+
+use crate::input::{Error, Input, Result};
+
+/// Where to read an input given on the command line from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InputSource {
+    Stdin,
+    File(PathBuf),
+}
+
+/// One day's input from an [`InputSource`], standing in for that day in another [`Input`]
+#[derive(Debug)]
+pub struct InputOverride<I> {
+    year: usize,
+    day: usize,
+    bytes: Arc<[u8]>,
+    /// `None` if the input isn't UTF-8
+    text: Option<Arc<str>>,
+    rest: I,
+}
+
+impl<I: Input> InputOverride<I> {
+    /// Read all of `source` up front, as stdin can only be read once and solvers may ask for their
+    /// input more than that
+    pub async fn load(source: &InputSource, year: usize, day: usize, rest: I) -> Result<Self> {
+        let bytes = match source {
+            InputSource::Stdin => {
+                let mut bytes = Vec::new();
+                tokio::io::stdin().read_to_end(&mut bytes).await?;
+                bytes
+            },
+            InputSource::File(path) => read_file(path).await?,
+        };
+        let text = std::str::from_utf8(&bytes).ok().map(Arc::from);
+
+        Ok(Self {
+            year,
+            day,
+            bytes: bytes.into(),
+            text,
+            rest,
+        })
+    }
+
+    fn overrides(&self, year: usize, day: usize) -> bool {
+        (year, day) == (self.year, self.day)
+    }
+}
+
+async fn read_file(path: &Path) -> Result<Vec<u8>> {
+    match tokio::fs::read(path).await {
+        Ok(bytes) => Ok(bytes),
+        Err(err) if err.kind() == ErrorKind::NotFound => Err(Error::NoInputFile(path.into())),
+        Err(err) => Err(err.into()),
+    }
+}
+
+impl<I: Input> Input for InputOverride<I> {
+    fn has_input(&self, year: usize, day: usize) -> bool {
+        self.overrides(year, day) || self.rest.has_input(year, day)
+    }
+
+    fn get_input(&self, year: usize, day: usize) -> Result<Arc<str>> {
+        if !self.overrides(year, day) {
+            return self.rest.get_input(year, day);
+        }
+
+        self.text.clone().ok_or(Error::Utf8Error(year, day))
+    }
+
+    fn get_bytes(&self, year: usize, day: usize) -> Result<Arc<[u8]>> {
+        if !self.overrides(year, day) {
+            return self.rest.get_bytes(year, day);
+        }
+
+        Ok(self.bytes.clone())
+    }
+
+    fn reader(&self, year: usize, day: usize) -> Result<Box<dyn BufRead + Send>> {
+        if !self.overrides(year, day) {
+            return self.rest.reader(year, day);
+        }
+
+        Ok(Box::new(Cursor::new(self.bytes.clone())))
+    }
+}
+
+impl FromStr for InputSource {
+    type Err = Infallible;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "-" => Ok(Self::Stdin),
+            path => Ok(Self::File(path.into())),
+        }
+    }
+}
+
+impl fmt::Display for InputSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Stdin => write!(f, "stdin"),
+            Self::File(path) => write!(f, "{}", path.display()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::input::SimpleInput;
+
+    #[tokio::test]
+    async fn test_load() {
+        let path =
+            std::env::temp_dir().join(format!("aoc24-input-override-{}", std::process::id()));
+        tokio::fs::write(&path, "3   4\n4   3\n").await.unwrap();
+
+        let source = "-".parse::<InputSource>().unwrap();
+        assert_eq!(source, InputSource::Stdin);
+
+        let source = path.to_str().unwrap().parse::<InputSource>().unwrap();
+        let input = InputOverride::load(&source, 2024, 1, SimpleInput::new("downloaded"))
+            .await
+            .unwrap();
+        let missing = InputOverride::load(
+            &InputSource::File(path.with_extension("missing")),
+            2024,
+            1,
+            SimpleInput::new("downloaded"),
+        )
+        .await;
+        tokio::fs::remove_file(&path).await.unwrap();
+
+        assert_eq!(&*input.get_input(2024, 1).unwrap(), "3   4\n4   3\n");
+        assert_eq!(&*input.get_bytes(2024, 1).unwrap(), b"3   4\n4   3\n");
+        assert_eq!(&*input.get_input(2024, 2).unwrap(), "downloaded");
+        assert_eq!(&*input.get_input(2023, 1).unwrap(), "downloaded");
+        assert!(matches!(missing, Err(Error::NoInputFile(_))));
+    }
+}
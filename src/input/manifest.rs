@@ -0,0 +1,266 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Local, SecondsFormat};
+use sha2::{Digest, Sha256};
+use tokio::fs;
+
+use crate::input::download::write_atomic;
+use crate::input::{Error, Result};
+
+/// What an input looked like when it was downloaded, to tell later whether it's been damaged or
+/// changed since
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ManifestEntry {
+    pub hash: String,
+    pub size: usize,
+    pub lines: usize,
+    pub downloaded: DateTime<Local>,
+}
+
+/// Something wrong with a saved input, from [`check`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Problem {
+    /// Saved before the manifest was kept, so there's nothing to compare with
+    Unrecorded,
+    Empty,
+    Html,
+    Truncated {
+        size: usize,
+        expected: usize,
+    },
+    Crlf,
+    Edited,
+}
+
+impl ManifestEntry {
+    pub fn new(input: &[u8], downloaded: DateTime<Local>) -> Self {
+        Self {
+            hash: hash(input),
+            size: input.len(),
+            lines: count_lines(input),
+            downloaded,
+        }
+    }
+}
+
+/// The manifest of every downloaded input, one `<year>:<day> <sha256> <bytes> <lines> <time>` entry
+/// per line
+#[derive(Debug, Clone)]
+pub struct Manifest {
+    path: PathBuf,
+    entries: BTreeMap<(usize, usize), ManifestEntry>,
+}
+
+impl Manifest {
+    pub async fn load<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let text = match fs::read_to_string(&path).await {
+            Ok(text) => text,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => String::new(),
+            Err(err) => return Err(err.into()),
+        };
+
+        let entries = text
+            .lines()
+            .filter(|line| !line.trim().is_empty() && !line.starts_with('#'))
+            .map(Self::parse_entry)
+            .collect::<Result<_>>()?;
+
+        Ok(Self { path, entries })
+    }
+
+    fn parse_entry(line: &str) -> Result<((usize, usize), ManifestEntry)> {
+        let invalid = || Error::ManifestError(format!("invalid entry `{}`", line));
+
+        let [day, hash, size, lines, downloaded] = line.split_whitespace().collect::<Vec<_>>()[..]
+        else {
+            return Err(invalid());
+        };
+
+        let (year, day) = day.split_once(':').ok_or_else(invalid)?;
+        let entry = ManifestEntry {
+            hash: hash.to_string(),
+            size: size.parse().map_err(|_| invalid())?,
+            lines: lines.parse().map_err(|_| invalid())?,
+            downloaded: DateTime::parse_from_rfc3339(downloaded)
+                .map_err(|_| invalid())?
+                .with_timezone(&Local),
+        };
+
+        Ok((
+            (
+                year.parse().map_err(|_| invalid())?,
+                day.parse().map_err(|_| invalid())?,
+            ),
+            entry,
+        ))
+    }
+
+    pub async fn save(&self) -> Result<()> {
+        let mut text = String::from("# downloaded inputs, see `aoc24 check-inputs`\n");
+        for ((year, day), entry) in &self.entries {
+            text.push_str(&format!(
+                "{}:{} {} {} {} {}\n",
+                year,
+                day,
+                entry.hash,
+                entry.size,
+                entry.lines,
+                entry.downloaded.to_rfc3339_opts(SecondsFormat::Secs, false)
+            ));
+        }
+
+        write_atomic(&self.path, text.as_bytes()).await
+    }
+
+    pub fn get(&self, year: usize, day: usize) -> Option<&ManifestEntry> {
+        self.entries.get(&(year, day))
+    }
+
+    pub fn insert(&mut self, year: usize, day: usize, entry: ManifestEntry) {
+        self.entries.insert((year, day), entry);
+    }
+}
+
+/// Look for signs that `input` isn't what was downloaded as `entry`, or isn't an input at all
+pub fn check(input: &[u8], entry: Option<&ManifestEntry>) -> Option<Problem> {
+    if input.trim_ascii().is_empty() {
+        return Some(Problem::Empty);
+    }
+    if is_html(input) {
+        return Some(Problem::Html);
+    }
+
+    let Some(entry) = entry else {
+        return Some(Problem::Unrecorded);
+    };
+    if hash(input) == entry.hash {
+        return None;
+    }
+
+    let unix_endings = String::from_utf8_lossy(input).replace("\r\n", "\n");
+    if unix_endings.len() < input.len() && hash(unix_endings.as_bytes()) == entry.hash {
+        return Some(Problem::Crlf);
+    }
+
+    // Inputs end with a newline, which a cut short download loses along with the rest
+    if input.len() < entry.size && !input.ends_with(b"\n") {
+        return Some(Problem::Truncated {
+            size: input.len(),
+            expected: entry.size,
+        });
+    }
+
+    Some(Problem::Edited)
+}
+
+/// Whether `body` is a web page, as served in place of input for errors
+pub(super) fn is_html(body: &[u8]) -> bool {
+    let start = String::from_utf8_lossy(body.trim_ascii_start()).to_ascii_lowercase();
+    start.starts_with("<!doctype") || start.starts_with("<html")
+}
+
+fn hash(input: &[u8]) -> String {
+    Sha256::digest(input)
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+/// Lines the way [`str::lines`] counts them, where a final newline doesn't start another
+fn count_lines(input: &[u8]) -> usize {
+    let newlines = input.iter().filter(|&&b| b == b'\n').count();
+    match input.last() {
+        None | Some(b'\n') => newlines,
+        Some(_) => newlines + 1,
+    }
+}
+
+impl Problem {
+    /// Whether the input is known to be wrong, rather than just not recorded
+    pub fn is_damage(&self) -> bool {
+        *self != Self::Unrecorded
+    }
+}
+
+impl fmt::Display for Problem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Unrecorded => write!(
+                f,
+                "not in the manifest, it was saved before inputs were recorded"
+            ),
+            Self::Empty => write!(f, "empty"),
+            Self::Html => write!(f, "a web page rather than puzzle input"),
+            Self::Truncated { size, expected } => {
+                write!(f, "truncated, {} of {} bytes", size, expected)
+            },
+            Self::Crlf => write!(f, "line endings converted to CRLF, as some editors do"),
+            Self::Edited => write!(f, "changed since it was downloaded"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INPUT: &[u8] = b"3   4\n4   3\n2   5\n";
+
+    #[test]
+    fn test_check() {
+        let entry = ManifestEntry::new(INPUT, Local::now());
+        assert_eq!((entry.size, entry.lines), (18, 3));
+
+        assert_eq!(check(INPUT, Some(&entry)), None);
+        assert_eq!(check(INPUT, None), Some(Problem::Unrecorded));
+        assert_eq!(check(b"\n", Some(&entry)), Some(Problem::Empty));
+        assert_eq!(
+            check(b"<!DOCTYPE html>\n<html>", Some(&entry)),
+            Some(Problem::Html)
+        );
+        assert_eq!(
+            check(b"3   4\r\n4   3\r\n2   5\r\n", Some(&entry)),
+            Some(Problem::Crlf)
+        );
+        assert_eq!(
+            check(b"3   4\n4   3\n2", Some(&entry)),
+            Some(Problem::Truncated {
+                size: 13,
+                expected: 18
+            })
+        );
+        assert_eq!(
+            check(b"3   4\n4   3\n2   6\n", Some(&entry)),
+            Some(Problem::Edited)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_manifest() {
+        let path = std::env::temp_dir().join(format!("aoc24-manifest-{}", std::process::id()));
+        let downloaded = DateTime::parse_from_rfc3339("2024-12-01T05:00:03Z")
+            .unwrap()
+            .with_timezone(&Local);
+
+        let mut manifest = Manifest::load(&path).await.unwrap();
+        assert!(manifest.get(2024, 1).is_none());
+        manifest.insert(2024, 1, ManifestEntry::new(INPUT, downloaded));
+        manifest.save().await.unwrap();
+
+        let loaded = Manifest::load(&path).await;
+        fs::write(&path, "2024:1 abc 18 three 2024-12-01T05:00:03Z\n")
+            .await
+            .unwrap();
+        let invalid = Manifest::load(&path).await;
+        fs::remove_file(&path).await.unwrap();
+
+        assert_eq!(
+            loaded.unwrap().get(2024, 1),
+            Some(&ManifestEntry::new(INPUT, downloaded))
+        );
+        assert!(matches!(invalid, Err(Error::ManifestError(_))));
+    }
+}
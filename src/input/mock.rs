@@ -0,0 +1,242 @@
+//! Stand-in for the Advent of Code site, serving canned pages on localhost so everything that
+//! talks to it can be tested without the network
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::task::JoinHandle;
+use url::Url;
+
+use crate::input::{Client, ClientOptions};
+
+pub const SESSION: &str = "mock-session";
+
+/// A canned response
+#[derive(Debug, Clone)]
+pub struct Reply {
+    pub status: u16,
+    pub body: String,
+}
+
+impl Reply {
+    pub fn new(status: u16, body: impl Into<String>) -> Self {
+        Self {
+            status,
+            body: body.into(),
+        }
+    }
+
+    pub fn ok(body: impl Into<String>) -> Self {
+        Self::new(200, body)
+    }
+
+    /// What the input endpoint says without a valid session
+    pub fn login_required() -> Self {
+        Self::new(400, include_str!("../../fixtures/mock/login_required"))
+    }
+
+    /// What the input endpoint says before the puzzle unlocks
+    pub fn not_unlocked() -> Self {
+        Self::new(404, include_str!("../../fixtures/mock/not_unlocked"))
+    }
+
+    /// A proxy error page, as served while the site is overloaded
+    pub fn server_error() -> Self {
+        Self::new(502, include_str!("../../fixtures/mock/server_error.html"))
+    }
+}
+
+/// A request the server received
+#[derive(Debug, Clone)]
+pub struct Request {
+    pub method: String,
+    pub path: String,
+    pub headers: Vec<(String, String)>,
+    pub body: String,
+}
+
+impl Request {
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+/// Replies for each `METHOD /path`, given in turn, with the last one repeated once the rest
+/// are used up
+type Routes = HashMap<String, VecDeque<Reply>>;
+
+#[derive(Debug, Default)]
+pub struct MockServer {
+    routes: Routes,
+}
+
+#[derive(Debug)]
+pub struct RunningServer {
+    url: Url,
+    requests: Arc<Mutex<Vec<Request>>>,
+    handle: JoinHandle<()>,
+}
+
+impl MockServer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The parts of 2024 the fixtures cover: the logged in calendar, day 1's puzzle page and
+    /// input, and a correct answer to it. Anything else is a 404.
+    pub fn advent() -> Self {
+        Self::new()
+            .route(
+                "GET",
+                "/2024",
+                Reply::ok(include_str!("../../fixtures/mock/calendar.html")),
+            )
+            .route(
+                "GET",
+                "/2024/day/1",
+                Reply::ok(include_str!("../../fixtures/mock/day1.html")),
+            )
+            .route(
+                "GET",
+                "/2024/day/1/input",
+                Reply::ok(include_str!("../../fixtures/mock/day1_input")),
+            )
+            .route(
+                "POST",
+                "/2024/day/1/answer",
+                Reply::ok(include_str!("../../fixtures/submit/correct.html")),
+            )
+    }
+
+    /// Add a reply to `method` requests for `path`. Calling this again for the same route queues
+    /// up another reply for the request after.
+    pub fn route(mut self, method: &str, path: &str, reply: Reply) -> Self {
+        self.routes
+            .entry(format!("{} {}", method, path))
+            .or_default()
+            .push_back(reply);
+        self
+    }
+
+    pub async fn start(self) -> RunningServer {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = Url::parse(&format!("http://{}/", listener.local_addr().unwrap())).unwrap();
+        let requests = Arc::new(Mutex::new(Vec::new()));
+
+        let routes = Arc::new(Mutex::new(self.routes));
+        let received = requests.clone();
+        let handle = tokio::spawn(async move {
+            loop {
+                let Ok((stream, _)) = listener.accept().await else {
+                    return;
+                };
+                tokio::spawn(serve(stream, routes.clone(), received.clone()));
+            }
+        });
+
+        RunningServer {
+            url,
+            requests,
+            handle,
+        }
+    }
+}
+
+impl RunningServer {
+    /// A client for this server which doesn't wait between requests
+    pub fn client(&self) -> Client {
+        Client::new(&ClientOptions {
+            base_url: self.url.clone(),
+            min_interval: Duration::ZERO,
+            ..Default::default()
+        })
+        .unwrap()
+    }
+
+    /// Every request received so far, in order
+    pub fn requests(&self) -> Vec<Request> {
+        self.requests.lock().unwrap().clone()
+    }
+}
+
+impl Drop for RunningServer {
+    fn drop(&mut self) {
+        self.handle.abort();
+    }
+}
+
+async fn serve(
+    mut stream: TcpStream,
+    routes: Arc<Mutex<Routes>>,
+    requests: Arc<Mutex<Vec<Request>>>,
+) {
+    let Some(request) = read_request(&mut stream).await else {
+        return;
+    };
+
+    let reply = {
+        let mut routes = routes.lock().unwrap();
+        match routes.get_mut(&format!("{} {}", request.method, request.path)) {
+            Some(replies) if replies.len() > 1 => replies.pop_front(),
+            Some(replies) => replies.front().cloned(),
+            None => None,
+        }
+    }
+    .unwrap_or_else(|| Reply::new(404, "404 Not Found"));
+    requests.lock().unwrap().push(request);
+
+    let response = format!(
+        "HTTP/1.1 {} Mock\r\ncontent-type: text/html\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{}",
+        reply.status,
+        reply.body.len(),
+        reply.body
+    );
+    let _ = stream.write_all(response.as_bytes()).await;
+}
+
+async fn read_request(stream: &mut TcpStream) -> Option<Request> {
+    let mut received = Vec::new();
+    let mut buf = [0; 4096];
+
+    loop {
+        let n = stream.read(&mut buf).await.ok()?;
+        if n == 0 {
+            return None;
+        }
+        received.extend_from_slice(&buf[..n]);
+
+        let text = String::from_utf8_lossy(&received);
+        let Some((head, body)) = text.split_once("\r\n\r\n") else {
+            continue;
+        };
+
+        let mut lines = head.lines();
+        let mut request_line = lines.next()?.split_whitespace();
+        let method = request_line.next()?.to_string();
+        let path = request_line.next()?.to_string();
+        let headers: Vec<(String, String)> = lines
+            .filter_map(|line| line.split_once(':'))
+            .map(|(key, value)| (key.trim().to_string(), value.trim().to_string()))
+            .collect();
+
+        let length = headers
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case("content-length"))
+            .and_then(|(_, value)| value.parse::<usize>().ok())
+            .unwrap_or(0);
+        if body.len() >= length {
+            return Some(Request {
+                method,
+                path,
+                headers,
+                body: body.to_string(),
+            });
+        }
+    }
+}
@@ -0,0 +1,240 @@
+use crate::input::{Error, Result};
+
+/// A day's puzzle description, as Markdown converted from the `<article>` for each unlocked part
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Puzzle {
+    pub year: usize,
+    pub day: usize,
+    pub parts: Vec<String>,
+}
+
+impl Puzzle {
+    pub fn parse_page(year: usize, day: usize, page: &str) -> Result<Self> {
+        let parts: Vec<String> = page
+            .split("<article")
+            .skip(1)
+            .filter_map(|rest| rest.split_once('>'))
+            .filter_map(|(_, rest)| rest.split_once("</article>"))
+            .map(|(article, _)| to_markdown(article))
+            .collect();
+
+        if parts.is_empty() {
+            return Err(Error::UnrecognizedResponse(format!(
+                "no puzzle description on {} day {} page",
+                year, day
+            )));
+        }
+
+        Ok(Self { year, day, parts })
+    }
+
+    /// Read back a puzzle cached by [`Puzzle::to_markdown`]
+    pub fn from_markdown(year: usize, day: usize, markdown: &str) -> Self {
+        let parts = markdown
+            .split(PART_SEPARATOR)
+            .map(|part| part.trim().to_string())
+            .filter(|part| !part.is_empty())
+            .collect();
+
+        Self { year, day, parts }
+    }
+
+    pub fn to_markdown(&self) -> String {
+        self.parts.join(PART_SEPARATOR)
+    }
+
+    /// The text of every code block, in order through both parts. These are usually the
+    /// puzzle's examples, along with the odd diagram.
+    pub fn examples(&self) -> Vec<String> {
+        let mut examples = Vec::new();
+        let mut block: Option<String> = None;
+
+        for line in self.parts.iter().flat_map(|part| part.lines()) {
+            match (&mut block, line.starts_with("```")) {
+                (None, true) => block = Some(String::new()),
+                (Some(_), true) => examples.extend(block.take()),
+                (Some(text), false) => {
+                    text.push_str(line);
+                    text.push('\n');
+                },
+                (None, false) => (),
+            }
+        }
+
+        examples
+    }
+}
+
+/// Between parts in a cached puzzle, which [`to_markdown`] never produces itself
+const PART_SEPARATOR: &str = "\n\n---\n\n";
+
+/// Convert the handful of HTML elements puzzle descriptions use into Markdown
+fn to_markdown(html: &str) -> String {
+    let mut out = String::new();
+    let mut in_pre = false;
+    // Where the open inline `<code>` began, and whether it holds an `<em>`
+    let mut code: Option<(usize, bool)> = None;
+    let mut links = Vec::new();
+
+    let mut rest = html;
+    while !rest.is_empty() {
+        let (text, tag) = match rest.split_once('<') {
+            Some((text, after)) => match after.split_once('>') {
+                Some((tag, after)) => {
+                    rest = after;
+                    (text, Some(tag))
+                },
+                None => {
+                    rest = "";
+                    (text, None)
+                },
+            },
+            None => {
+                let text = rest;
+                rest = "";
+                (text, None)
+            },
+        };
+
+        let text = decode_entities(text);
+        if in_pre || code.is_some() {
+            out.push_str(&text);
+        } else {
+            push_text(&mut out, &text);
+        }
+
+        let Some(tag) = tag else {
+            break;
+        };
+        let closing = tag.starts_with('/');
+        let name = tag
+            .trim_start_matches('/')
+            .split(|c: char| c.is_whitespace() || c == '/')
+            .next()
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+
+        match (name.as_str(), closing) {
+            ("h2", false) => out.push_str("## "),
+            ("h2" | "p" | "ul", true) => out.push_str("\n\n"),
+            ("pre", false) => {
+                in_pre = true;
+                out.push_str("```\n");
+            },
+            ("pre", true) => {
+                in_pre = false;
+                if !out.ends_with('\n') {
+                    out.push('\n');
+                }
+                out.push_str("```\n\n");
+            },
+            ("code", false) if !in_pre => {
+                code = Some((out.len(), false));
+                out.push('`');
+            },
+            ("code", true) if !in_pre => {
+                out.push('`');
+                if let Some((start, true)) = code {
+                    out.insert(start, '*');
+                    out.push('*');
+                }
+                code = None;
+            },
+            ("em", _) if in_pre => (),
+            ("em", false) => match &mut code {
+                Some((_, emphasized)) => *emphasized = true,
+                None => out.push('*'),
+            },
+            ("em", true) if code.is_none() => out.push('*'),
+            ("li", false) => out.push_str("- "),
+            ("li", true) | ("br", _) => out.push('\n'),
+            ("a", false) => {
+                links.push(attribute(tag, "href").unwrap_or_default());
+                out.push('[');
+            },
+            ("a", true) => {
+                let href = links.pop().unwrap_or_default();
+                out.push_str(&format!("]({})", href));
+            },
+            _ => (),
+        }
+    }
+
+    out.trim().to_string()
+}
+
+/// Push prose, escaping anything Markdown would treat as markup and collapsing whitespace the
+/// way HTML does
+fn push_text(out: &mut String, text: &str) {
+    for c in text.chars() {
+        if c.is_whitespace() {
+            if !out.ends_with([' ', '\n']) && !out.is_empty() {
+                out.push(' ');
+            }
+        } else {
+            if matches!(c, '*' | '`' | '[' | ']' | '\\' | '_') {
+                out.push('\\');
+            }
+            out.push(c);
+        }
+    }
+}
+
+fn attribute(tag: &str, name: &str) -> Option<String> {
+    let (_, rest) = tag.split_once(&format!("{}=\"", name))?;
+    let (value, _) = rest.split_once('"')?;
+
+    Some(decode_entities(value))
+}
+
+fn decode_entities(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_markdown() {
+        let html = r#"<h2>--- Day 1: Historian Hysteria ---</h2><p>The <em>Chief Historian</em> is in a <a href="/2024/about">[location]</a> with 2 * 3 rooms.</p>
+<pre><code>3   4
+<em>4</em>   3
+</code></pre>
+<ul><li>Pair <code>1</code> with <code>3</code>.</li><li>Total: <code><em>11</em></code></li></ul>"#;
+
+        assert_eq!(
+            to_markdown(html),
+            "## --- Day 1: Historian Hysteria ---\n\n\
+             The *Chief Historian* is in a [\\[location\\]](/2024/about) with 2 \\* 3 rooms.\n\n\
+             ```\n3   4\n4   3\n```\n\n\
+             - Pair `1` with `3`.\n- Total: *`11`*"
+        );
+    }
+
+    #[test]
+    fn test_parse_page() {
+        let puzzle =
+            Puzzle::parse_page(2024, 1, include_str!("../../fixtures/mock/day1.html")).unwrap();
+        assert_eq!(puzzle.parts.len(), 1);
+        assert!(puzzle.parts[0].starts_with("## --- Day 1: Historian Hysteria ---"));
+        assert!(puzzle.parts[0].contains("the total distance is *`11`*."));
+        assert_eq!(
+            puzzle.examples(),
+            vec![include_str!("../../fixtures/mock/day1_input")]
+        );
+
+        assert_eq!(
+            Puzzle::from_markdown(2024, 1, &puzzle.to_markdown()),
+            puzzle,
+            "cached puzzles read back the same"
+        );
+
+        assert!(Puzzle::parse_page(2024, 1, "<html>oops</html>").is_err());
+    }
+}
@@ -0,0 +1,167 @@
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use tokio::fs;
+
+use crate::config;
+use crate::input::{Client, Error, Result};
+
+pub const SESSION_ENV: &str = "AOC_SESSION";
+const SESSION_FILE: &str = ".session";
+const CONFIG_SESSION_FILE: &str = "session";
+
+/// Where a session cookie was found
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SessionSource {
+    Flag,
+    Environment,
+    ProjectFile(PathBuf),
+    UserConfig(PathBuf),
+}
+
+/// Advent of Code session cookie, which identifies the user and so their puzzle input
+#[derive(Clone)]
+pub struct Session {
+    token: String,
+    source: SessionSource,
+}
+
+impl Session {
+    pub fn new(token: &str, source: SessionSource) -> Option<Self> {
+        let token = token.trim();
+        let token = token.strip_prefix("session=").unwrap_or(token);
+
+        if token.is_empty() {
+            None
+        } else {
+            Some(Self {
+                token: token.to_string(),
+                source,
+            })
+        }
+    }
+
+    /// Find the session from, in order: the `--session` flag, the `AOC_SESSION` environment
+    /// variable, a `.session` file in the current directory or any parent, and the user's
+    /// config directory
+    pub async fn resolve(flag: Option<&str>) -> Result<Self> {
+        if let Some(session) = flag.and_then(|token| Self::new(token, SessionSource::Flag)) {
+            return Ok(session);
+        }
+
+        if let Some(session) = std::env::var(SESSION_ENV)
+            .ok()
+            .and_then(|token| Self::new(&token, SessionSource::Environment))
+        {
+            return Ok(session);
+        }
+
+        let current_dir = std::env::current_dir()?;
+        for dir in current_dir.ancestors() {
+            let path = dir.join(SESSION_FILE);
+            if let Some(session) = Self::read(&path, SessionSource::ProjectFile).await? {
+                return Ok(session);
+            }
+        }
+
+        if let Some(path) = Self::user_config_path() {
+            if let Some(session) = Self::read(&path, SessionSource::UserConfig).await? {
+                return Ok(session);
+            }
+        }
+
+        Err(Error::NoSession)
+    }
+
+    pub fn user_config_path() -> Option<PathBuf> {
+        config::user_config_path(CONFIG_SESSION_FILE)
+    }
+
+    async fn read(path: &Path, source: fn(PathBuf) -> SessionSource) -> Result<Option<Self>> {
+        match fs::read_to_string(path).await {
+            Ok(contents) => {
+                let token = contents.lines().next().unwrap_or_default();
+                Ok(Self::new(token, source(path.to_path_buf())))
+            },
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    pub fn token(&self) -> &str {
+        &self.token
+    }
+
+    pub fn source(&self) -> &SessionSource {
+        &self.source
+    }
+
+    pub fn cookie(&self) -> String {
+        format!("session={}", self.token)
+    }
+
+    /// Fetch an authenticated page, the calendar for `year`, to check the session is still
+    /// valid, returning the name of the logged in user
+    pub async fn check(&self, client: &Client, year: usize) -> Result<String> {
+        let req = client
+            .get(client.url(&[&year.to_string()])?)
+            .header("Cookie", self.cookie())
+            .build()?;
+
+        let res = client.execute(req).await?;
+        if res.status.is_server_error() {
+            return Err(Error::ServerError(res.status));
+        }
+
+        Self::parse_user(&res.text()).ok_or(Error::SessionExpired)
+    }
+
+    fn parse_user(page: &str) -> Option<String> {
+        let (_, rest) = page.split_once(r#"<div class="user">"#)?;
+        let (user, _) = rest.split_once('<')?;
+        let user = user.trim();
+
+        (!user.is_empty()).then(|| user.to_string())
+    }
+}
+
+impl fmt::Debug for Session {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Session")
+            .field("token", &"<redacted>")
+            .field("source", &self.source)
+            .finish()
+    }
+}
+
+impl fmt::Display for SessionSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Flag => write!(f, "--session"),
+            Self::Environment => write!(f, "{}", SESSION_ENV),
+            Self::ProjectFile(path) | Self::UserConfig(path) => write!(f, "{}", path.display()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_new() {
+        let session = Session::new(" session=53616c7465645f5f\n", SessionSource::Flag).unwrap();
+        assert_eq!(session.token(), "53616c7465645f5f");
+
+        assert!(Session::new("  ", SessionSource::Environment).is_none());
+    }
+
+    #[test]
+    fn test_parse_user() {
+        let logged_in = r#"<header><div><div class="user">Ada Lovelace <span class="star-count">12*</span></div></div></header>"#;
+        assert_eq!(Session::parse_user(logged_in), Some("Ada Lovelace".into()));
+
+        let logged_out = r#"<header><div><a href="/2024/auth/login">[Log In]</a></div></header>"#;
+        assert_eq!(Session::parse_user(logged_out), None);
+    }
+}
@@ -1,25 +1,30 @@
+use std::sync::Arc;
+
 use crate::input::{Input, Result};
 
 #[derive(Debug)]
 pub struct SimpleInput {
-    input: String,
+    input: Arc<str>,
 }
 
 impl SimpleInput {
-    #[cfg(test)]
     pub fn new<S: Into<String>>(input: S) -> SimpleInput {
         Self {
-            input: input.into(),
+            input: input.into().into(),
         }
     }
 }
 
 impl Input for SimpleInput {
-    fn has_input(&self, _: usize) -> bool {
+    fn has_input(&self, _: usize, _: usize) -> bool {
         true
     }
 
-    fn get_input(&mut self, _: usize) -> Result<String> {
+    fn get_input(&self, _: usize, _: usize) -> Result<Arc<str>> {
         Ok(self.input.clone())
     }
+
+    fn get_bytes(&self, _: usize, _: usize) -> Result<Arc<[u8]>> {
+        Ok(self.input.as_bytes().into())
+    }
 }
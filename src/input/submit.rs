@@ -0,0 +1,379 @@
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::time::Duration;
+
+use chrono::{Local, SecondsFormat};
+use itertools::Itertools;
+use reqwest::StatusCode;
+use tokio::fs::{self, OpenOptions};
+use tokio::io::AsyncWriteExt;
+use winnow::prelude::*;
+
+use crate::challenge::Answer;
+use crate::input::download::Download;
+use crate::input::{Client, Error, Result};
+use crate::select::Challenge;
+
+/// How Advent of Code responded to a submitted answer
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Outcome {
+    Correct,
+    Incorrect,
+    TooHigh,
+    TooLow,
+    RateLimited(Duration),
+    AlreadyCompleted,
+}
+
+impl Outcome {
+    pub fn parse_page(page: &str) -> Result<Self> {
+        let message = page
+            .split_once("<article>")
+            .and_then(|(_, rest)| rest.split_once("</article>"))
+            .map(|(article, _)| article)
+            .ok_or_else(|| Error::UnrecognizedResponse("no article in page".into()))?;
+
+        if message.contains("That's the right answer") {
+            Ok(Self::Correct)
+        } else if message.contains("That's not the right answer") {
+            if message.contains("your answer is too high") {
+                Ok(Self::TooHigh)
+            } else if message.contains("your answer is too low") {
+                Ok(Self::TooLow)
+            } else {
+                Ok(Self::Incorrect)
+            }
+        } else if message.contains("You gave an answer too recently") {
+            let wait = message
+                .split_once("You have ")
+                .and_then(|(_, rest)| rest.split_once(" left to wait"))
+                .and_then(|(wait, _)| Self::parse_wait.parse(wait).ok())
+                .unwrap_or_default();
+
+            Ok(Self::RateLimited(wait))
+        } else if message.contains("You don't seem to be solving the right level") {
+            Ok(Self::AlreadyCompleted)
+        } else {
+            Err(Error::UnrecognizedResponse(message.trim().to_string()))
+        }
+    }
+
+    /// Parse wait times like `34s` or `1m 4s`
+    fn parse_wait(input: &mut &str) -> PResult<Duration> {
+        use winnow::ascii::{dec_uint, space0};
+        use winnow::combinator::{alt, repeat, terminated};
+
+        let unit = alt(('h'.value(3600), 'm'.value(60), 's'.value(1)));
+        repeat(1.., terminated((dec_uint::<_, u64, _>, unit), space0))
+            .fold(
+                || 0,
+                |total, (amount, unit): (u64, u64)| total + amount * unit,
+            )
+            .map(Duration::from_secs)
+            .parse_next(input)
+    }
+
+    pub fn is_wrong(&self) -> bool {
+        matches!(self, Self::Incorrect | Self::TooHigh | Self::TooLow)
+    }
+
+    fn log_name(&self) -> &'static str {
+        match self {
+            Self::Correct => "correct",
+            Self::Incorrect => "incorrect",
+            Self::TooHigh => "too-high",
+            Self::TooLow => "too-low",
+            Self::RateLimited(_) => "rate-limited",
+            Self::AlreadyCompleted => "already-completed",
+        }
+    }
+}
+
+impl FromStr for Outcome {
+    type Err = Error;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "correct" => Ok(Self::Correct),
+            "incorrect" => Ok(Self::Incorrect),
+            "too-high" => Ok(Self::TooHigh),
+            "too-low" => Ok(Self::TooLow),
+            "rate-limited" => Ok(Self::RateLimited(Duration::ZERO)),
+            "already-completed" => Ok(Self::AlreadyCompleted),
+            _ => Err(Error::SubmissionLogError(format!(
+                "unknown outcome `{}`",
+                s
+            ))),
+        }
+    }
+}
+
+impl fmt::Display for Outcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Correct => write!(f, "correct"),
+            Self::Incorrect => write!(f, "incorrect"),
+            Self::TooHigh => write!(f, "incorrect, too high"),
+            Self::TooLow => write!(f, "incorrect, too low"),
+            Self::RateLimited(wait) => write!(f, "rate limited, wait {:?}", wait),
+            Self::AlreadyCompleted => write!(f, "already completed"),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct Submitter {
+    client: Client,
+    session: String,
+}
+
+impl Submitter {
+    pub fn new(download: &Download) -> Self {
+        Self {
+            client: download.client().clone(),
+            session: download.session().token().to_string(),
+        }
+    }
+
+    pub async fn submit(&self, challenge: Challenge, answer: &Answer) -> Result<Outcome> {
+        let url = self.client.url(&[
+            &challenge.year.to_string(),
+            "day",
+            &challenge.day.to_string(),
+            "answer",
+        ])?;
+
+        let form = [
+            ("level", (challenge.part as i32).to_string()),
+            ("answer", answer.to_string()),
+        ];
+
+        let req = self
+            .client
+            .post(url)
+            .header("Cookie", format!("session={}", self.session))
+            .form(&form)
+            .build()?;
+
+        let res = self.client.execute(req).await?;
+        match res.status {
+            StatusCode::BAD_REQUEST | StatusCode::UNAUTHORIZED => {
+                return Err(Error::SessionExpired)
+            },
+            status if status.is_server_error() => return Err(Error::ServerError(status)),
+            _ => (),
+        }
+
+        Outcome::parse_page(&res.text())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Attempt {
+    pub challenge: Challenge,
+    pub outcome: Outcome,
+    pub answer: Answer,
+}
+
+/// Every submitted answer, one `<time> <year>:<day>p<part> <outcome> <answer>` entry per line
+#[derive(Debug, Clone)]
+pub struct SubmissionLog {
+    path: PathBuf,
+}
+
+impl SubmissionLog {
+    pub fn new<P: AsRef<Path>>(path: P) -> Self {
+        Self {
+            path: path.as_ref().to_path_buf(),
+        }
+    }
+
+    pub async fn record(&self, attempt: &Attempt) -> Result<()> {
+        let line = format!(
+            "{} {} {} {}\n",
+            Local::now().to_rfc3339_opts(SecondsFormat::Secs, false),
+            attempt.challenge.to_entry(),
+            attempt.outcome.log_name(),
+            attempt.answer
+        );
+
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .await?;
+        file.write_all(line.as_bytes()).await?;
+
+        Ok(())
+    }
+
+    pub async fn attempts(&self, challenge: Challenge) -> Result<Vec<Attempt>> {
+        let text = match fs::read_to_string(&self.path).await {
+            Ok(text) => text,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(err) => return Err(err.into()),
+        };
+
+        let attempts = text
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(Self::parse_entry)
+            .filter_ok(|attempt| attempt.challenge == challenge)
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(attempts)
+    }
+
+    fn parse_entry(line: &str) -> Result<Attempt> {
+        let invalid = || Error::SubmissionLogError(format!("invalid entry `{}`", line));
+
+        let mut fields = line.splitn(4, ' ').skip(1);
+        let (Some(challenge), Some(outcome), Some(answer)) =
+            (fields.next(), fields.next(), fields.next())
+        else {
+            return Err(invalid());
+        };
+
+        let challenge = challenge.parse().map_err(|_| invalid())?;
+        let outcome = outcome.parse()?;
+        let Ok(answer) = answer.parse();
+
+        Ok(Attempt {
+            challenge,
+            outcome,
+            answer,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::input::mock::{MockServer, Reply, RunningServer, SESSION};
+    use crate::select::ChallengePart;
+
+    fn submitter(server: &RunningServer) -> Submitter {
+        Submitter {
+            client: server.client(),
+            session: SESSION.into(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_submit() {
+        let server = MockServer::advent().start().await;
+        let challenge = Challenge::new(2024, 1, ChallengePart::Second);
+
+        let outcome = submitter(&server)
+            .submit(challenge, &Answer::from(31))
+            .await
+            .unwrap();
+        assert_eq!(outcome, Outcome::Correct);
+
+        let requests = server.requests();
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0].method, "POST");
+        assert_eq!(requests[0].path, "/2024/day/1/answer");
+        assert_eq!(
+            requests[0].header("cookie"),
+            Some(format!("session={}", SESSION).as_str())
+        );
+        assert_eq!(requests[0].body, "level=2&answer=31");
+    }
+
+    #[tokio::test]
+    async fn test_outcomes() {
+        let pages = [
+            (
+                include_str!("../../fixtures/submit/incorrect.html"),
+                Outcome::Incorrect,
+            ),
+            (
+                include_str!("../../fixtures/submit/too_high.html"),
+                Outcome::TooHigh,
+            ),
+            (
+                include_str!("../../fixtures/submit/too_low.html"),
+                Outcome::TooLow,
+            ),
+            (
+                include_str!("../../fixtures/submit/rate_limited.html"),
+                Outcome::RateLimited(Duration::from_secs(94)),
+            ),
+            (
+                include_str!("../../fixtures/submit/already_completed.html"),
+                Outcome::AlreadyCompleted,
+            ),
+        ];
+
+        for (page, expected) in pages {
+            let server = MockServer::new()
+                .route("POST", "/2024/day/1/answer", Reply::ok(page))
+                .start()
+                .await;
+            let outcome = submitter(&server)
+                .submit(
+                    Challenge::new(2024, 1, ChallengePart::First),
+                    &Answer::from(1),
+                )
+                .await
+                .unwrap();
+
+            assert_eq!(outcome, expected);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_submit_errors() {
+        let server = MockServer::new()
+            .route("POST", "/2024/day/1/answer", Reply::new(400, ""))
+            .route("POST", "/2024/day/1/answer", Reply::server_error())
+            .start()
+            .await;
+        let submitter = submitter(&server);
+        let challenge = Challenge::new(2024, 1, ChallengePart::First);
+
+        assert!(matches!(
+            submitter.submit(challenge, &Answer::from(1)).await,
+            Err(Error::SessionExpired)
+        ));
+        assert!(matches!(
+            submitter.submit(challenge, &Answer::from(1)).await,
+            Err(Error::ServerError(StatusCode::BAD_GATEWAY))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_log() {
+        let path = std::env::temp_dir().join(format!("aoc24-submissions-{}", std::process::id()));
+        let log = SubmissionLog::new(&path);
+        let first = Challenge::new(2024, 3, ChallengePart::First);
+
+        let attempts = [
+            (first, Outcome::TooLow, Answer::from(10)),
+            (
+                Challenge::new(2024, 3, ChallengePart::Second),
+                Outcome::Correct,
+                Answer::from(5),
+            ),
+            (first, Outcome::Correct, Answer::from(12)),
+        ];
+        let attempts = attempts.map(|(challenge, outcome, answer)| Attempt {
+            challenge,
+            outcome,
+            answer,
+        });
+        for attempt in &attempts {
+            log.record(attempt).await.unwrap();
+        }
+
+        let recorded = log.attempts(first).await.unwrap();
+        let text = fs::read_to_string(&path).await.unwrap();
+        fs::remove_file(&path).await.unwrap();
+
+        assert_eq!(recorded, vec![attempts[0].clone(), attempts[2].clone()]);
+        let (_, entry) = text.lines().next().unwrap().split_once(' ').unwrap();
+        assert_eq!(entry, "2024:3p1 too-low 10");
+    }
+}
@@ -1,48 +1,974 @@
+mod answers;
+mod calendar;
 mod challenge;
+mod config;
 mod error;
 mod input;
+mod markdown;
+mod output;
+mod progress;
 mod select;
+mod status;
 
-use std::sync::{Arc, Mutex};
+use std::collections::BTreeMap;
+use std::io::IsTerminal;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::sync::{Arc, OnceLock};
+use std::time::Duration;
 
-use clap::{value_parser, Parser};
+use clap::{value_parser, Parser, Subcommand};
 use itertools::Itertools;
+use url::Url;
 
-use crate::challenge::solve_all;
+use crate::answers::{KnownAnswers, Verdict};
+use crate::challenge::bench::{bench_all, BenchOptions, BenchSummary};
+use crate::challenge::{registry, solve_all, solve_stream, Answer, Solution, Timeouts};
 use crate::error::Error;
-use crate::input::{Download, InputFiles};
-use crate::select::MultiChallengeSelector;
+use crate::input::{
+    Attempt, Client, ClientOptions, Download, EncryptedInput, Input, InputFiles, InputOverride,
+    InputSet, InputSource, Key, Manifest, Outcome, Session, SubmissionLog, Submitter,
+    DEFAULT_BASE_URL, KEY_FILE,
+};
+use crate::output::OutputFormat;
+use crate::progress::ProgressTable;
+use crate::select::{Challenge, ChallengePart, MultiChallengeSelector};
+use crate::status::{Status, Summary, EXIT_CODES};
+
+const INPUT_DIR: &str = "input";
+const ANSWERS_FILE: &str = "answers";
+const SUBMISSIONS_FILE: &str = "submissions";
 
 #[derive(Parser, Debug)]
-#[command(author, version, about, long_about = None)]
+#[command(
+    author,
+    version,
+    about,
+    long_about = None,
+    after_help = EXIT_CODES
+)]
 struct Args {
+    #[command(subcommand)]
+    command: Option<Command>,
+
     #[arg(value_parser = value_parser!(MultiChallengeSelector))]
     parts: Vec<MultiChallengeSelector>,
+
+    #[command(flatten)]
+    options: Options,
 }
 
-#[tokio::main]
-async fn main() {
-    if let Err(err) = run().await {
-        eprintln!("error: {}", err);
+/// Options for every command which solves challenges
+#[derive(clap::Args, Debug)]
+struct Options {
+    /// Give up on each challenge after this long, e.g. `30s`
+    #[arg(long, global = true, value_parser = humantime::parse_duration)]
+    timeout: Option<Duration>,
+
+    /// Give up on every challenge still running after this long
+    #[arg(long, global = true, value_parser = humantime::parse_duration)]
+    total_timeout: Option<Duration>,
+
+    /// How to write solutions
+    #[arg(short, long, global = true, value_enum, default_value_t)]
+    output: OutputFormat,
+
+    /// Year of challenges selected without one, instead of the configured or current year
+    #[arg(short, long, global = true, env = "AOC_YEAR", value_parser = clap::value_parser!(u32).range(calendar::FIRST_YEAR as i64..))]
+    year: Option<u32>,
+
+    /// Solve against the Kth example from each puzzle instead of the real input
+    #[arg(short, long, global = true, value_name = "K", value_parser = clap::value_parser!(u32).range(1..))]
+    example: Option<u32>,
+
+    /// Read the selected day's input from this file instead, or from stdin for `-`
+    #[arg(
+        short,
+        long,
+        global = true,
+        value_name = "PATH",
+        conflicts_with = "example"
+    )]
+    input: Option<InputSource>,
+
+    /// Where inputs, puzzle descriptions and examples are downloaded to. Accepted answers and the
+    /// submission log are kept alongside it.
+    #[arg(long, global = true, env = "AOC_INPUT_DIR", default_value = INPUT_DIR)]
+    input_dir: PathBuf,
+
+    /// Key for inputs stored encrypted. While it exists, inputs are downloaded encrypted too.
+    #[arg(long, global = true, env = "AOC_KEY_FILE", default_value = KEY_FILE)]
+    key_file: PathBuf,
+
+    /// Wait for puzzles which haven't unlocked yet and download them when they do
+    #[arg(long, global = true)]
+    wait: bool,
+
+    /// Advent of Code session cookie, instead of AOC_SESSION or a session file
+    #[arg(long, global = true)]
+    session: Option<String>,
+
+    /// Root of the Advent of Code site, e.g. to point at a mirror or a local test server
+    #[arg(long, global = true, env = "AOC_BASE_URL", default_value = DEFAULT_BASE_URL)]
+    base_url: Url,
+
+    /// Contact details sent with every request, so Advent of Code can reach you
+    #[arg(long, global = true, env = "AOC_CONTACT")]
+    contact: Option<String>,
+
+    /// Minimum time between requests to Advent of Code
+    #[arg(long, global = true, default_value = "1s", value_parser = humantime::parse_duration)]
+    request_interval: Duration,
+
+    /// Most requests to Advent of Code in flight at once
+    #[arg(long, global = true, default_value_t = 2, value_parser = clap::value_parser!(u32).range(1..))]
+    max_requests: u32,
+}
+
+impl Options {
+    /// The year for selections which don't give one: `--year` or `AOC_YEAR`, then the year in
+    /// the user's config, then the most recent Advent of Code
+    fn year(&self) -> Result<usize, Error> {
+        if let Some(year) = self.year {
+            return Ok(year as usize);
+        }
+
+        match config::default_year()? {
+            Some(year) => Ok(year),
+            None => Ok(calendar::current_year(chrono::Utc::now())),
+        }
+    }
+
+    fn select(&self, parts: &[MultiChallengeSelector]) -> Result<Vec<Challenge>, Error> {
+        let year = self.year()?;
+        let challenges = MultiChallengeSelector::select_all(parts, year);
+
+        // `all` only covers registered solvers, so can come up empty for a new year
+        if challenges.is_empty() && !parts.is_empty() {
+            return Err(Error::InvalidSelectionError(format!(
+                "no solvers registered for the selected {} days, pick another year with --year",
+                year
+            )));
+        }
+
+        Ok(challenges)
+    }
+
+    async fn session(&self) -> Result<Session, Error> {
+        Ok(Session::resolve(self.session.as_deref()).await?)
+    }
+
+    /// The client every request goes through, so they all share one set of limits
+    fn client(&self) -> Result<Client, Error> {
+        static CLIENT: OnceLock<Client> = OnceLock::new();
+
+        if let Some(client) = CLIENT.get() {
+            return Ok(client.clone());
+        }
+
+        let client = Client::new(&ClientOptions {
+            base_url: self.base_url.clone(),
+            contact: self.contact.clone(),
+            min_interval: self.request_interval,
+            max_concurrent: self.max_requests as usize,
+        })?;
+        Ok(CLIENT.get_or_init(|| client).clone())
+    }
+
+    fn input_files(&self) -> Result<InputFiles, Error> {
+        Ok(InputFiles::new(&self.input_dir)?)
+    }
+
+    /// `name` next to the input directory, so records move with `--input-dir` rather than
+    /// depending on where the command runs
+    fn data_file(&self, name: &str) -> PathBuf {
+        self.input_dir.parent().unwrap_or(Path::new("")).join(name)
+    }
+
+    fn answers_file(&self) -> PathBuf {
+        self.data_file(ANSWERS_FILE)
+    }
+
+    fn submissions_file(&self) -> PathBuf {
+        self.data_file(SUBMISSIONS_FILE)
+    }
+
+    async fn key(&self) -> Result<Option<Key>, Error> {
+        Ok(Key::load(&self.key_file).await?)
+    }
+
+    /// Refuse to `action` with `--example` or `--input`, which would compare answers for another
+    /// input with the accepted ones
+    fn require_real_input(&self, action: &'static str) -> Result<(), Error> {
+        if self.example.is_some() {
+            return Err(Error::InputOptionError("--example", action));
+        }
+        if self.input.is_some() {
+            return Err(Error::InputOptionError("--input", action));
+        }
+
+        Ok(())
+    }
+
+    fn timeouts(&self) -> Timeouts {
+        Timeouts {
+            challenge: self.timeout,
+            total: self.total_timeout,
+        }
     }
 }
 
-async fn run() -> Result<(), Error> {
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// List the registered solvers
+    List,
+    /// Benchmark the selected challenges with repeated runs
+    Bench {
+        /// Untimed runs of each challenge before measuring
+        #[arg(short, long, default_value_t = 3)]
+        warmup: usize,
+
+        /// Timed runs of each challenge
+        #[arg(short, long, default_value_t = 10, value_parser = clap::value_parser!(u32).range(1..))]
+        runs: u32,
+
+        #[arg(value_parser = value_parser!(MultiChallengeSelector), required = true)]
+        parts: Vec<MultiChallengeSelector>,
+    },
+    /// Solve the selected challenges and check them against the accepted answers
+    Verify {
+        #[arg(value_parser = value_parser!(MultiChallengeSelector), required = true)]
+        parts: Vec<MultiChallengeSelector>,
+    },
+    /// Solve the selected challenges against your input and every input set under `input/sets/`,
+    /// checking each against its own accepted answers
+    VerifySets {
+        /// Only check these input sets, along with your input
+        #[arg(short, long = "set", value_name = "NAME")]
+        sets: Vec<String>,
+
+        #[arg(value_parser = value_parser!(MultiChallengeSelector), required = true)]
+        parts: Vec<MultiChallengeSelector>,
+    },
+    /// Solve the selected challenges and record their answers as accepted
+    Accept {
+        /// Replace accepted answers which differ from the new solution
+        #[arg(short, long)]
+        force: bool,
+
+        #[arg(value_parser = value_parser!(MultiChallengeSelector), required = true)]
+        parts: Vec<MultiChallengeSelector>,
+    },
+    /// Solve a challenge and submit the answer to Advent of Code
+    Submit {
+        /// Submit this answer instead of solving the challenge
+        #[arg(short, long)]
+        answer: Option<String>,
+
+        /// Challenge to submit, e.g. `5p2` or `2023:5p2`
+        #[arg(value_parser = value_parser!(MultiChallengeSelector))]
+        challenge: MultiChallengeSelector,
+    },
+    /// Show the puzzle description for the selected days
+    Read {
+        /// Download the description again instead of using the cached one
+        #[arg(short, long)]
+        refresh: bool,
+
+        #[arg(value_parser = value_parser!(MultiChallengeSelector), required = true)]
+        parts: Vec<MultiChallengeSelector>,
+    },
+    /// Encrypt every downloaded input, so the encrypted copies can be committed. Creates the key
+    /// file if there isn't one yet.
+    Encrypt,
+    /// Decrypt every encrypted input, e.g. after a fresh checkout
+    Decrypt,
+    /// Check saved inputs against the manifest recorded as they were downloaded, for truncated
+    /// files, error pages, converted line endings and local edits
+    CheckInputs {
+        /// Download flagged inputs again, replacing them
+        #[arg(short, long)]
+        redownload: bool,
+    },
+    /// Manage the Advent of Code session cookie
+    Session {
+        #[command(subcommand)]
+        command: SessionCommand,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum SessionCommand {
+    /// Check the session cookie is still logged in
+    Check,
+}
+
+fn main() -> ExitCode {
+    let runtime = match tokio::runtime::Runtime::new() {
+        Ok(runtime) => runtime,
+        Err(err) => {
+            eprintln!("error: {}", err);
+            return ExitCode::FAILURE;
+        },
+    };
+
+    let status = match runtime.block_on(run()) {
+        Ok(status) => status,
+        Err(err) => {
+            eprintln!("error: {}", err);
+            Status::InputError
+        },
+    };
+
+    // Timed out solvers which never check for cancellation would otherwise keep us from exiting
+    runtime.shutdown_background();
+    status.into()
+}
+
+async fn run() -> Result<Status, Error> {
     let args = Args::parse();
-    let challenges = args.parts.into_iter().flatten().collect_vec();
+    let options = &args.options;
 
-    let input = InputFiles::new("input")?;
-    {
-        let downloader = Download::new(&input).await?;
-        downloader.download_missing(&challenges).await?;
+    match args.command {
+        Some(Command::List) => list(),
+        Some(Command::Bench {
+            warmup,
+            runs,
+            parts,
+        }) => {
+            let bench_options = BenchOptions {
+                warmup,
+                runs: runs as usize,
+            };
+            bench(parts, bench_options, options).await
+        },
+        Some(Command::Verify { parts }) => verify(parts, options).await,
+        Some(Command::VerifySets { sets, parts }) => verify_sets(parts, sets, options).await,
+        Some(Command::Accept { force, parts }) => accept(parts, force, options).await,
+        Some(Command::Submit { answer, challenge }) => submit(challenge, answer, options).await,
+        Some(Command::Read { refresh, parts }) => read(parts, refresh, options).await,
+        Some(Command::Encrypt) => encrypt(options).await,
+        Some(Command::Decrypt) => decrypt(options).await,
+        Some(Command::CheckInputs { redownload }) => check_inputs(redownload, options).await,
+        Some(Command::Session {
+            command: SessionCommand::Check,
+        }) => session_check(options).await,
+        None => solve(args.parts, options).await,
+    }
+}
+
+fn list() -> Result<Status, Error> {
+    for registration in registry::registrations() {
+        println!(
+            "\t{} Day {:>2}: {}",
+            registration.year,
+            registration.day,
+            registration.title.unwrap_or_default()
+        );
+    }
+
+    Ok(Status::Success)
+}
+
+async fn solve(parts: Vec<MultiChallengeSelector>, options: &Options) -> Result<Status, Error> {
+    let solutions = follow_selection(parts, options, |_| ()).await?;
+    print_summary(&solutions, options.output);
+
+    Ok(Status::of_solutions(&solutions))
+}
+
+async fn verify(parts: Vec<MultiChallengeSelector>, options: &Options) -> Result<Status, Error> {
+    options.require_real_input("verify answers")?;
+    let known = KnownAnswers::load(options.answers_file())?;
+    let solutions = follow_selection(parts, options, |solution| {
+        known.verify(std::slice::from_mut(solution))
+    })
+    .await?;
+
+    print_summary(&solutions, options.output);
+
+    Ok(Status::of_solutions(&solutions))
+}
+
+/// Label for your own input among the input sets
+const OWN_INPUT: &str = "own input";
+
+async fn verify_sets(
+    parts: Vec<MultiChallengeSelector>,
+    names: Vec<String>,
+    options: &Options,
+) -> Result<Status, Error> {
+    options.require_real_input("verify input sets")?;
+    let challenges = options.select(&parts)?;
+
+    let input = options.input_files()?;
+    let key = options.key().await?;
+    let sets = if names.is_empty() {
+        input.sets()?
+    } else {
+        names
+            .iter()
+            .map(|name| input.set(name))
+            .collect::<Result<Vec<InputSet>, _>>()?
+    };
+    if sets.is_empty() {
+        return Err(Error::InvalidSelectionError(format!(
+            "no input sets to verify, add a directory for each under {}",
+            options.input_dir.join("sets").display()
+        )));
+    }
+
+    let mut runs = vec![(
+        OWN_INPUT.to_string(),
+        prepare_input(&challenges, options).await?,
+        KnownAnswers::load(options.answers_file())?,
+    )];
+    for set in sets {
+        let known = KnownAnswers::load(set.answers_path())?;
+        runs.push((set.name, with_key(set.input, &key), known));
+    }
+
+    let mut status = Status::Success;
+    // Which sets each challenge passed and failed for
+    let mut results: BTreeMap<Challenge, (Vec<String>, Vec<String>)> = BTreeMap::new();
+
+    for (name, input, known) in runs {
+        // Teammates won't have done every day, so only solve the ones this set has input for
+        let (present, missing): (Vec<Challenge>, Vec<Challenge>) = challenges
+            .iter()
+            .partition(|challenge| input.has_input(challenge.year, challenge.day));
+        for (year, day) in missing.iter().map(|c| (c.year, c.day)).dedup() {
+            println!("\t{}: no input for {} day {}, skipping it", name, year, day);
+        }
+
+        let mut solutions = solve_all(present, input, options.timeouts()).await;
+        known.verify(&mut solutions);
+
+        for solution in &solutions {
+            println!("\t{}: {}", name, solution);
+
+            let (passed, failed) = results.entry(solution.challenge).or_default();
+            match (&solution.solution, &solution.verdict) {
+                (Ok(_), Some(Verdict::Correct)) => passed.push(name.clone()),
+                (Ok(_), Some(Verdict::Unknown) | None) => (),
+                _ => failed.push(name.clone()),
+            }
+        }
+        println!("\t{}: {}", name, Summary::new(&solutions));
+        status = status.max(Status::of_solutions(&solutions));
+    }
+
+    // A challenge right for some inputs and not others is likely relying on a quirk of the inputs
+    // it was written against
+    for (challenge, (passed, failed)) in results {
+        if !passed.is_empty() && !failed.is_empty() {
+            println!(
+                "\t{}: correct for {} but not {}",
+                challenge,
+                passed.join(", "),
+                failed.join(", ")
+            );
+        }
+    }
+
+    Ok(status)
+}
+
+fn print_summary(solutions: &[Solution], output: OutputFormat) {
+    let summary = Summary::new(solutions);
+
+    // Keep stdout parseable when writing machine-readable output
+    if output == OutputFormat::Text {
+        println!("\t{}", summary);
+    } else {
+        eprintln!("\t{}", summary);
     }
+}
 
-    let input = Arc::new(Mutex::new(input));
-    let solutions = solve_all(challenges, input.clone()).await;
+async fn accept(
+    parts: Vec<MultiChallengeSelector>,
+    force: bool,
+    options: &Options,
+) -> Result<Status, Error> {
+    options.require_real_input("accept answers")?;
+    let mut known = KnownAnswers::load(options.answers_file())?;
+    let solutions = solve_selection(parts, options).await?;
+    let mut status = Status::of_solutions(&solutions);
+    let summary = Summary::new(&solutions);
 
     for solution in solutions {
-        println!("\t{}", solution)
+        let Ok(answer) = solution.solution else {
+            println!("\t{}", solution);
+            continue;
+        };
+
+        match known.verdict(&solution.challenge, &answer) {
+            Verdict::Wrong(accepted) if !force => {
+                println!(
+                    "\t{}: {} differs from accepted answer {}, use --force to replace it",
+                    solution.challenge, answer, accepted
+                );
+                status = status.max(Status::Failure);
+            },
+            _ => {
+                println!("\t{}: {} accepted", solution.challenge, answer);
+                known.insert(solution.challenge, answer);
+            },
+        }
+    }
+
+    known.save()?;
+    println!("\t{}", summary);
+
+    Ok(status)
+}
+
+async fn submit(
+    challenge: MultiChallengeSelector,
+    answer: Option<String>,
+    options: &Options,
+) -> Result<Status, Error> {
+    options.require_real_input("submit answers")?;
+    let [challenge] = options.select(&[challenge])?[..] else {
+        return Err(Error::InvalidSelectionError(
+            "submit one part at a time, e.g. `5p2`".into(),
+        ));
+    };
+
+    let mut known = KnownAnswers::load(options.answers_file())?;
+    let log = SubmissionLog::new(options.submissions_file());
+
+    let answer = match answer {
+        Some(answer) => {
+            let Ok(answer) = answer.parse::<Answer>();
+            answer
+        },
+        None => {
+            let solution = solve_challenges(vec![challenge], options).await?.remove(0);
+            match &solution.solution {
+                Ok(answer) => answer.clone(),
+                Err(err) => {
+                    println!("\t{}", solution);
+                    return Ok(Status::of_error(err));
+                },
+            }
+        },
+    };
+
+    if let Some(accepted) = known.get(&challenge) {
+        println!(
+            "\t{}: already accepted {}, not submitting {}",
+            challenge, accepted, answer
+        );
+        return Ok(Status::Success);
     }
 
-    Ok(())
+    let previous = log.attempts(challenge).await?;
+    if let Some(attempt) = previous
+        .iter()
+        .find(|attempt| attempt.answer == answer && attempt.outcome.is_wrong())
+    {
+        println!(
+            "\t{}: {} was already submitted ({}), not submitting again",
+            challenge, answer, attempt.outcome
+        );
+        return Ok(Status::Failure);
+    }
+
+    let input = options.input_files()?;
+    let downloader = Download::new(&input, options.session().await?, options.client()?);
+    let outcome = Submitter::new(&downloader)
+        .submit(challenge, &answer)
+        .await?;
+
+    println!("\t{}: {} is {}", challenge, answer, outcome);
+
+    let attempt = Attempt {
+        challenge,
+        outcome,
+        answer,
+    };
+    log.record(&attempt).await?;
+
+    match attempt.outcome {
+        Outcome::Correct => {
+            known.insert(challenge, attempt.answer);
+            known.save()?;
+
+            // Solving part one unlocks part two, so a cached description is now out of date
+            if challenge.part == ChallengePart::First {
+                if let Err(err) = downloader
+                    .refresh_puzzle(challenge.year, challenge.day)
+                    .await
+                {
+                    eprintln!(
+                        "\tcouldn't refresh {} day {} puzzle: {}",
+                        challenge.year, challenge.day, err
+                    );
+                }
+            }
+
+            Ok(Status::Success)
+        },
+        Outcome::AlreadyCompleted => Ok(Status::Success),
+        _ => Ok(Status::Failure),
+    }
+}
+
+async fn read(
+    parts: Vec<MultiChallengeSelector>,
+    refresh: bool,
+    options: &Options,
+) -> Result<Status, Error> {
+    let days = options
+        .select(&parts)?
+        .into_iter()
+        .map(|challenge| (challenge.year, challenge.day))
+        .sorted()
+        .dedup()
+        .collect_vec();
+
+    let input = options.input_files()?;
+    let downloader = Download::new(&input, options.session().await?, options.client()?);
+    let styled = std::io::stdout().is_terminal();
+
+    for (year, day) in days {
+        let puzzle = downloader.puzzle(year, day, refresh).await?;
+        let markdown = puzzle.to_markdown();
+        if styled {
+            println!("{}", markdown::render(&markdown));
+        } else {
+            println!("{}\n", markdown);
+        }
+    }
+
+    Ok(Status::Success)
+}
+
+async fn encrypt(options: &Options) -> Result<Status, Error> {
+    let files = options.input_files()?;
+    let key = match options.key().await? {
+        Some(key) => key,
+        None => {
+            let key = Key::generate();
+            key.save(&options.key_file).await?;
+            println!(
+                "\tcreated key file {}, share it privately and never commit it",
+                options.key_file.display()
+            );
+            key
+        },
+    };
+
+    let input = EncryptedInput::new(files.clone(), key);
+    let (mut encrypted, mut unchanged) = (0, 0);
+    for (year, day) in files.saved_days()? {
+        if !files.filepath(year, day).exists() {
+            continue;
+        }
+
+        if input.encrypt(year, day).await? {
+            println!("\tencrypted {} day {}", year, day);
+            encrypted += 1;
+        } else {
+            unchanged += 1;
+        }
+    }
+    println!(
+        "\t{} encrypted, {} already up to date",
+        encrypted, unchanged
+    );
+
+    Ok(Status::Success)
+}
+
+async fn decrypt(options: &Options) -> Result<Status, Error> {
+    let files = options.input_files()?;
+    let key = options
+        .key()
+        .await?
+        .ok_or_else(|| input::Error::NoKey(options.key_file.clone()))?;
+
+    let input = EncryptedInput::new(files.clone(), key);
+    let (mut decrypted, mut unchanged) = (0, 0);
+    for (year, day) in files.saved_days()? {
+        if !files.encrypted_path(year, day).exists() {
+            continue;
+        }
+
+        if input.decrypt(year, day).await? {
+            println!("\tdecrypted {} day {}", year, day);
+            decrypted += 1;
+        } else {
+            unchanged += 1;
+        }
+    }
+    println!(
+        "\t{} decrypted, {} already up to date",
+        decrypted, unchanged
+    );
+
+    Ok(Status::Success)
+}
+
+async fn check_inputs(redownload: bool, options: &Options) -> Result<Status, Error> {
+    let files = options.input_files()?;
+    let key = options.key().await?;
+    let manifest = Manifest::load(files.manifest_path()).await?;
+    let input = with_key(files.clone(), &key);
+
+    let mut flagged = Vec::new();
+    for (year, day) in files.saved_days()? {
+        let problem = match input.get_bytes(year, day) {
+            Ok(bytes) => input::check(&bytes, manifest.get(year, day)),
+            Err(err) => {
+                println!("\t{} day {}: {}", year, day, err);
+                flagged.push((year, day));
+                continue;
+            },
+        };
+
+        match problem {
+            None => println!("\t{} day {}: ok", year, day),
+            Some(problem) => {
+                println!("\t{} day {}: {}", year, day, problem);
+                if problem.is_damage() {
+                    flagged.push((year, day));
+                }
+            },
+        }
+    }
+
+    if flagged.is_empty() {
+        return Ok(Status::Success);
+    }
+    if !redownload {
+        println!(
+            "\t{} damaged, download them again with --redownload",
+            flagged.len()
+        );
+        return Ok(Status::InputError);
+    }
+
+    let downloaded = Download::new(&files, options.session().await?, options.client()?)
+        .with_key(key)
+        .redownload(&flagged)
+        .await?;
+    for download in downloaded {
+        println!("\tdownloaded {} day {} again", download.year, download.day);
+    }
+
+    Ok(Status::Success)
+}
+
+async fn session_check(options: &Options) -> Result<Status, Error> {
+    let session = options.session().await?;
+    println!("\tusing session from {}", session.source());
+
+    let input = options.input_files()?;
+    let user = Download::new(&input, session, options.client()?)
+        .check_session(options.year()?)
+        .await?;
+    println!("\tsession is valid, logged in as {}", user);
+
+    Ok(Status::Success)
+}
+
+async fn solve_selection(
+    parts: Vec<MultiChallengeSelector>,
+    options: &Options,
+) -> Result<Vec<Solution>, Error> {
+    solve_challenges(options.select(&parts)?, options).await
+}
+
+/// Solve the selection while showing each challenge's progress
+async fn follow_selection(
+    parts: Vec<MultiChallengeSelector>,
+    options: &Options,
+    inspect: impl FnMut(&mut Solution),
+) -> Result<Vec<Solution>, Error> {
+    let challenges = options.select(&parts)?;
+    let input = prepare_input(&challenges, options).await?;
+
+    match options.output {
+        OutputFormat::Text => {
+            let table = ProgressTable::new(&challenges);
+            let progress = solve_stream(challenges, input, options.timeouts());
+            Ok(table.follow(progress, inspect).await)
+        },
+        _ => {
+            let progress = solve_stream(challenges, input, options.timeouts());
+            Ok(output::follow(options.output, progress, inspect).await?)
+        },
+    }
+}
+
+async fn solve_challenges(
+    challenges: Vec<Challenge>,
+    options: &Options,
+) -> Result<Vec<Solution>, Error> {
+    let input = prepare_input(&challenges, options).await?;
+
+    Ok(solve_all(challenges, input, options.timeouts()).await)
+}
+
+async fn bench(
+    parts: Vec<MultiChallengeSelector>,
+    bench_options: BenchOptions,
+    options: &Options,
+) -> Result<Status, Error> {
+    let challenges = options.select(&parts)?;
+    let input = prepare_input(&challenges, options).await?;
+
+    let benchmarks = bench_all(challenges, input.clone(), bench_options, options.timeouts()).await;
+
+    for benchmark in &benchmarks {
+        println!("\t{}", benchmark)
+    }
+    println!("\t{}", BenchSummary::new(&benchmarks));
+
+    let status = benchmarks
+        .iter()
+        .filter_map(|benchmark| benchmark.result.as_ref().err())
+        .map(Status::of_error)
+        .max()
+        .unwrap_or(Status::Success);
+    Ok(status)
+}
+
+/// Read `input` through `key`, if there is one, so encrypted inputs are found as well
+fn with_key(input: InputFiles, key: &Option<Key>) -> Arc<dyn Input> {
+    match key {
+        Some(key) => Arc::new(EncryptedInput::new(input, key.clone())),
+        None => Arc::new(input),
+    }
+}
+
+async fn prepare_input(
+    challenges: &[Challenge],
+    options: &Options,
+) -> Result<Arc<dyn Input>, Error> {
+    let input = options.input_files()?;
+    let key = options.key().await?;
+
+    // Nothing needs downloading when the one selected day's input is given
+    if let Some(source) = &options.input {
+        let days = challenges
+            .iter()
+            .map(|challenge| (challenge.year, challenge.day))
+            .sorted()
+            .dedup()
+            .collect_vec();
+        let [(year, day)] = days[..] else {
+            return Err(Error::InvalidSelectionError(format!(
+                "--input {} replaces one day's input, select parts of a single day",
+                source
+            )));
+        };
+
+        return Ok(Arc::new(
+            InputOverride::load(source, year, day, input).await?,
+        ));
+    }
+
+    // Days without a solver are only reported as missing, so there's no input to fetch for them
+    let challenges = &challenges
+        .iter()
+        .filter(|challenge| registry::is_registered(challenge.year, challenge.day))
+        .copied()
+        .collect_vec();
+
+    // Only downloading needs a session, so solving against what's saved works offline
+    if let Some(example) = options.example {
+        let example = example as usize;
+        let missing = challenges.iter().any(|challenge| {
+            !input
+                .example_path(challenge.year, challenge.day, example)
+                .exists()
+        });
+        if missing {
+            Download::new(&input, options.session().await?, options.client()?)
+                .download_examples(challenges, example)
+                .await?;
+        }
+        return Ok(Arc::new(input.with_example(Some(example))));
+    }
+
+    let saved = with_key(input.clone(), &key);
+    let missing = challenges
+        .iter()
+        .any(|challenge| !saved.has_input(challenge.year, challenge.day));
+    if !missing {
+        return Ok(saved);
+    }
+
+    let downloaded = Download::new(&input, options.session().await?, options.client()?)
+        .with_key(key)
+        .download_missing(challenges, options.wait)
+        .await?;
+    if !downloaded.is_empty() {
+        let days = downloaded
+            .iter()
+            .map(|download| match download.attempts {
+                1 => format!("{} day {} (1 attempt)", download.year, download.day),
+                n => format!("{} day {} ({} attempts)", download.year, download.day, n),
+            })
+            .join(", ");
+        eprintln!("\tdownloaded input for {}", days);
+    }
+
+    Ok(saved)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Args {
+        Args::try_parse_from(std::iter::once("aoc24").chain(args.iter().copied())).unwrap()
+    }
+
+    #[test]
+    fn test_global_options_before_subcommand() {
+        let args = parse(&["--timeout", "5s", "bench", "1"]);
+        assert!(matches!(args.command, Some(Command::Bench { .. })));
+        assert_eq!(args.options.timeout, Some(Duration::from_secs(5)));
+
+        let args = parse(&["-y", "2024", "verify", "1"]);
+        assert!(matches!(args.command, Some(Command::Verify { .. })));
+        assert_eq!(args.options.year, Some(2024));
+
+        let args = parse(&["--session", "X", "session", "check"]);
+        assert!(matches!(args.command, Some(Command::Session { .. })));
+        assert_eq!(args.options.session.as_deref(), Some("X"));
+
+        let args = parse(&["-o", "json", "list"]);
+        assert!(matches!(args.command, Some(Command::List)));
+        assert!(matches!(args.options.output, OutputFormat::Json));
+    }
+
+    #[test]
+    fn test_parts_without_subcommand() {
+        let args = parse(&["-y", "2024", "1", "2p1"]);
+        assert!(args.command.is_none());
+        assert_eq!(args.parts.len(), 2);
+
+        assert!(Args::try_parse_from(["aoc24", "1", "list"]).is_err());
+    }
+
+    #[test]
+    fn test_data_files() {
+        let args = parse(&["list"]);
+        assert_eq!(args.options.answers_file(), PathBuf::from("answers"));
+
+        let args = parse(&["--input-dir", "/data/aoc/input", "list"]);
+        assert_eq!(
+            args.options.answers_file(),
+            PathBuf::from("/data/aoc/answers")
+        );
+        assert_eq!(
+            args.options.submissions_file(),
+            PathBuf::from("/data/aoc/submissions")
+        );
+    }
 }
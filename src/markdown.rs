@@ -0,0 +1,98 @@
+//! Terminal rendering for the Markdown puzzle descriptions are cached as
+
+const RESET: &str = "\x1b[0m";
+const HEADING: &str = "\x1b[1;32m";
+const EMPHASIS: &str = "\x1b[1;97m";
+const CODE: &str = "\x1b[36m";
+const LINK: &str = "\x1b[4m";
+
+/// Style headings, emphasis, code and links with ANSI escapes, indenting code blocks
+pub fn render(markdown: &str) -> String {
+    let mut out = String::new();
+    let mut in_block = false;
+
+    for line in markdown.lines() {
+        if line.starts_with("```") {
+            in_block = !in_block;
+            continue;
+        }
+
+        if in_block {
+            out.push_str(&format!("    {}{}{}\n", CODE, line, RESET));
+        } else if let Some(heading) = line.strip_prefix("## ") {
+            out.push_str(&format!("{}{}{}\n", HEADING, unescape(heading), RESET));
+        } else if line == "---" {
+            out.push('\n');
+        } else {
+            out.push_str(&render_inline(line));
+            out.push('\n');
+        }
+    }
+
+    out
+}
+
+fn render_inline(line: &str) -> String {
+    let mut out = String::new();
+    let mut emphasis = false;
+    let mut chars = line.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => out.extend(chars.next()),
+            '*' => {
+                emphasis = !emphasis;
+                out.push_str(if emphasis { EMPHASIS } else { RESET });
+            },
+            '`' => {
+                let code: String = chars.by_ref().take_while(|&c| c != '`').collect();
+                out.push_str(&format!("{}{}{}", CODE, code, RESET));
+                // Code inside emphasis stays emphasized after it
+                if emphasis {
+                    out.push_str(EMPHASIS);
+                }
+            },
+            '[' => out.push_str(LINK),
+            ']' if chars.peek() == Some(&'(') => {
+                out.push_str(RESET);
+                // Drop the target, as it's relative to the site and no use in a terminal
+                chars.by_ref().take_while(|&c| c != ')').for_each(drop);
+            },
+            c => out.push(c),
+        }
+    }
+
+    out
+}
+
+fn unescape(text: &str) -> String {
+    let mut out = String::new();
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => out.extend(chars.next()),
+            c => out.push(c),
+        }
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render() {
+        let markdown = "## --- Day 1 ---\n\nThe *total* is *`11`*, not 2 \\* 3 [\\[x\\]](/2024).\n\n```\n3   4\n```";
+
+        assert_eq!(
+            render(markdown),
+            format!(
+                "{HEADING}--- Day 1 ---{RESET}\n\n\
+                 The {EMPHASIS}total{RESET} is {EMPHASIS}{CODE}11{RESET}{EMPHASIS}{RESET}, not 2 * 3 \
+                 {LINK}[x]{RESET}.\n\n    {CODE}3   4{RESET}\n"
+            )
+        );
+    }
+}
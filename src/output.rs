@@ -0,0 +1,310 @@
+use std::io::{self, Write};
+use std::time::Duration;
+
+use clap::ValueEnum;
+use itertools::Itertools;
+use serde::Serialize;
+use tokio::sync::mpsc;
+
+use crate::answers::Verdict;
+use crate::challenge::{Answer, Progress, Solution};
+
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    /// Live progress table, or plain lines when not writing to a terminal
+    #[default]
+    Text,
+    /// A single JSON array of solutions
+    Json,
+    /// One JSON object per solution, written as each one finishes
+    Ndjson,
+    Csv,
+    /// JUnit XML report with a test case per challenge
+    Junit,
+}
+
+/// A solution flattened into the fields shared by every machine-readable format
+#[derive(Debug, Serialize)]
+pub struct Record<'a> {
+    pub year: usize,
+    pub day: usize,
+    pub part: usize,
+    pub answer: Option<&'a Answer>,
+    pub verdict: Option<&'static str>,
+    pub expected: Option<&'a Answer>,
+    pub error_kind: Option<&'static str>,
+    pub error: Option<String>,
+    pub parse_ms: f64,
+    pub solve_ms: f64,
+}
+
+impl<'a> Record<'a> {
+    pub fn new(solution: &'a Solution) -> Self {
+        let (answer, error_kind, error) = match &solution.solution {
+            Ok(answer) => (Some(answer), None, None),
+            Err(err) => (None, Some(err.kind()), Some(err.to_string())),
+        };
+
+        let (verdict, expected) = match &solution.verdict {
+            Some(Verdict::Correct) => (Some("correct"), None),
+            Some(Verdict::Wrong(expected)) => (Some("wrong"), Some(expected)),
+            Some(Verdict::Unknown) => (Some("unknown"), None),
+            None => (None, None),
+        };
+
+        Self {
+            year: solution.challenge.year,
+            day: solution.challenge.day,
+            part: solution.challenge.part as usize,
+            answer,
+            verdict,
+            expected,
+            error_kind,
+            error,
+            parse_ms: millis(solution.timings.parse),
+            solve_ms: millis(solution.timings.solve),
+        }
+    }
+
+    /// Whether the challenge failed, either with an error or a wrong answer
+    pub fn failure(&self) -> Option<(&'static str, String)> {
+        match (self.error_kind, &self.error, self.expected) {
+            (Some(kind), Some(error), _) => Some((kind, error.clone())),
+            (_, _, Some(expected)) => Some(("wrong_answer", format!("expected {}", expected))),
+            _ => None,
+        }
+    }
+}
+
+fn millis(duration: Duration) -> f64 {
+    duration.as_nanos() as f64 / 1_000_000.0
+}
+
+/// Collect solutions from `progress`, passing each to `inspect` as it arrives, then write them
+/// all to stdout in `format`. NDJSON is written as solutions arrive rather than at the end.
+pub async fn follow(
+    format: OutputFormat,
+    mut progress: mpsc::UnboundedReceiver<Progress>,
+    mut inspect: impl FnMut(&mut Solution),
+) -> io::Result<Vec<Solution>> {
+    let mut solutions = Vec::new();
+    while let Some(event) = progress.recv().await {
+        if let Progress::Done(mut solution) = event {
+            inspect(&mut solution);
+            if format == OutputFormat::Ndjson {
+                let mut stdout = io::stdout().lock();
+                write_ndjson(&mut stdout, &solution)?;
+                stdout.flush()?;
+            }
+            solutions.push(solution);
+        }
+    }
+
+    solutions.sort();
+
+    let mut stdout = io::stdout().lock();
+    match format {
+        OutputFormat::Text | OutputFormat::Ndjson => (),
+        OutputFormat::Json => write_json(&mut stdout, &solutions)?,
+        OutputFormat::Csv => write_csv(&mut stdout, &solutions)?,
+        OutputFormat::Junit => write_junit(&mut stdout, &solutions)?,
+    }
+    stdout.flush()?;
+
+    Ok(solutions)
+}
+
+pub fn write_json(mut out: impl Write, solutions: &[Solution]) -> io::Result<()> {
+    let records = solutions.iter().map(Record::new).collect_vec();
+    serde_json::to_writer_pretty(&mut out, &records)?;
+    writeln!(out)
+}
+
+pub fn write_ndjson(mut out: impl Write, solution: &Solution) -> io::Result<()> {
+    serde_json::to_writer(&mut out, &Record::new(solution))?;
+    writeln!(out)
+}
+
+pub fn write_csv(mut out: impl Write, solutions: &[Solution]) -> io::Result<()> {
+    writeln!(
+        out,
+        "year,day,part,answer,verdict,expected,error_kind,error,parse_ms,solve_ms"
+    )?;
+
+    for record in solutions.iter().map(Record::new) {
+        let optional = |value: Option<String>| csv_field(&value.unwrap_or_default());
+        writeln!(
+            out,
+            "{},{},{},{},{},{},{},{},{},{}",
+            record.year,
+            record.day,
+            record.part,
+            optional(record.answer.map(Answer::to_string)),
+            record.verdict.unwrap_or_default(),
+            optional(record.expected.map(Answer::to_string)),
+            record.error_kind.unwrap_or_default(),
+            optional(record.error.clone()),
+            record.parse_ms,
+            record.solve_ms,
+        )?;
+    }
+
+    Ok(())
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+pub fn write_junit(mut out: impl Write, solutions: &[Solution]) -> io::Result<()> {
+    let records = solutions.iter().map(Record::new).collect_vec();
+    let failures = records
+        .iter()
+        .filter(|record| record.failure().is_some())
+        .count();
+    let seconds = |ms: f64| ms / 1000.0;
+    let total = records
+        .iter()
+        .map(|record| seconds(record.parse_ms + record.solve_ms))
+        .sum::<f64>();
+
+    writeln!(out, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+    writeln!(
+        out,
+        r#"<testsuites name="aoc24" tests="{}" failures="{}" time="{:.6}">"#,
+        records.len(),
+        failures,
+        total
+    )?;
+    writeln!(
+        out,
+        r#"  <testsuite name="aoc24" tests="{}" failures="{}" time="{:.6}">"#,
+        records.len(),
+        failures,
+        total
+    )?;
+
+    for (solution, record) in solutions.iter().zip(&records) {
+        writeln!(
+            out,
+            r#"    <testcase name="{}" classname="aoc24.y{}.day{}" time="{:.6}">"#,
+            solution.challenge,
+            record.year,
+            record.day,
+            seconds(record.parse_ms + record.solve_ms)
+        )?;
+
+        if let Some(answer) = record.answer {
+            writeln!(out, "      <properties>")?;
+            writeln!(
+                out,
+                r#"        <property name="answer" value="{}"/>"#,
+                xml_escape(&answer.to_string())
+            )?;
+            writeln!(out, "      </properties>")?;
+        }
+
+        if let Some((kind, message)) = record.failure() {
+            writeln!(
+                out,
+                r#"      <failure type="{}" message="{}"/>"#,
+                kind,
+                xml_escape(&message)
+            )?;
+        }
+
+        writeln!(out, "    </testcase>")?;
+    }
+
+    writeln!(out, "  </testsuite>")?;
+    writeln!(out, "</testsuites>")
+}
+
+fn xml_escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::challenge::{Error, Timings};
+    use crate::select::{Challenge, ChallengePart};
+
+    fn solutions() -> Vec<Solution> {
+        vec![
+            Solution {
+                challenge: Challenge::new(2024, 1, ChallengePart::First),
+                solution: Ok(11.into()),
+                timings: Timings::new(Duration::from_millis(2), Duration::from_millis(1)),
+                verdict: Some(Verdict::Correct),
+            },
+            Solution {
+                challenge: Challenge::new(2024, 1, ChallengePart::Second),
+                solution: Ok(30.into()),
+                timings: Timings::default(),
+                verdict: Some(Verdict::Wrong(31.into())),
+            },
+            Solution {
+                challenge: Challenge::new(2024, 6, ChallengePart::First),
+                solution: Err(Error::ParseError("bad map, \"row\" 3".into())),
+                timings: Timings::default(),
+                verdict: None,
+            },
+        ]
+    }
+
+    fn write(writer: fn(&mut Vec<u8>, &[Solution]) -> io::Result<()>) -> String {
+        let mut out = Vec::new();
+        writer(&mut out, &solutions()).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn test_json() {
+        let json: serde_json::Value =
+            serde_json::from_str(&write(|out, s| write_json(out, s))).unwrap();
+
+        assert_eq!(json[0]["year"], 2024);
+        assert_eq!(json[0]["day"], 1);
+        assert_eq!(json[0]["answer"], 11);
+        assert_eq!(json[0]["parse_ms"], 2.0);
+        assert_eq!(json[1]["verdict"], "wrong");
+        assert_eq!(json[1]["expected"], 31);
+        assert_eq!(json[2]["answer"], serde_json::Value::Null);
+        assert_eq!(json[2]["error_kind"], "parse");
+    }
+
+    #[test]
+    fn test_csv() {
+        let csv = write(|out, s| write_csv(out, s));
+        let lines = csv.lines().collect_vec();
+
+        assert_eq!(lines.len(), 4);
+        assert_eq!(lines[1], "2024,1,1,11,correct,,,,2,1");
+        assert_eq!(
+            lines[3],
+            r#"2024,6,1,,,,parse,"error parsing input: bad map, ""row"" 3",0,0"#
+        );
+    }
+
+    #[test]
+    fn test_junit() {
+        let xml = write(|out, s| write_junit(out, s));
+
+        assert!(xml.contains(r#"<testsuites name="aoc24" tests="3" failures="2""#));
+        assert!(xml.contains(r#"<property name="answer" value="11"/>"#));
+        assert!(xml.contains(r#"<failure type="wrong_answer" message="expected 31"/>"#));
+        assert!(xml.contains(
+            r#"<failure type="parse" message="error parsing input: bad map, &quot;row&quot; 3"/>"#
+        ));
+    }
+}
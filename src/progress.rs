@@ -0,0 +1,205 @@
+use std::io::{self, IsTerminal, Write};
+use std::time::{Duration, Instant};
+
+use itertools::Itertools;
+use tokio::sync::mpsc;
+use tokio::time::MissedTickBehavior;
+
+use crate::challenge::{Progress, Solution};
+use crate::select::Challenge;
+
+const REDRAW_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Table of the selected challenges, redrawn in place as their solutions come in.
+///
+/// When stdout isn't a terminal nothing is drawn until the end, when every solution is printed
+/// on its own line in challenge order.
+#[derive(Debug)]
+pub struct ProgressTable {
+    rows: Vec<Row>,
+    live: bool,
+    drawn: usize,
+}
+
+#[derive(Debug)]
+struct Row {
+    challenge: Challenge,
+    state: State,
+}
+
+#[derive(Debug)]
+enum State {
+    Pending,
+    Running(Instant),
+    Done(Solution, Duration),
+}
+
+impl ProgressTable {
+    pub fn new(challenges: &[Challenge]) -> Self {
+        let rows = challenges
+            .iter()
+            .copied()
+            .sorted()
+            .dedup()
+            .map(|challenge| Row {
+                challenge,
+                state: State::Pending,
+            })
+            .collect();
+
+        Self {
+            rows,
+            live: io::stdout().is_terminal(),
+            drawn: 0,
+        }
+    }
+
+    /// Follow `progress` until every challenge is done, passing each solution to `inspect` as it
+    /// arrives, and return the solutions in challenge order
+    pub async fn follow(
+        mut self,
+        mut progress: mpsc::UnboundedReceiver<Progress>,
+        mut inspect: impl FnMut(&mut Solution),
+    ) -> Vec<Solution> {
+        let mut redraw = tokio::time::interval(REDRAW_INTERVAL);
+        redraw.set_missed_tick_behavior(MissedTickBehavior::Skip);
+
+        loop {
+            tokio::select! {
+                event = progress.recv() => match event {
+                    Some(Progress::Running(challenge)) => {
+                        self.set(challenge, State::Running(Instant::now()))
+                    },
+                    Some(Progress::Done(mut solution)) => {
+                        inspect(&mut solution);
+                        let elapsed = match self.row(solution.challenge).map(|row| &row.state) {
+                            Some(State::Running(started)) => started.elapsed(),
+                            _ => Duration::ZERO,
+                        };
+                        self.set(solution.challenge, State::Done(solution, elapsed));
+                    },
+                    None => break,
+                },
+                _ = redraw.tick() => (),
+            }
+
+            self.draw();
+        }
+
+        self.finish()
+    }
+
+    fn row(&self, challenge: Challenge) -> Option<&Row> {
+        self.rows.iter().find(|row| row.challenge == challenge)
+    }
+
+    fn set(&mut self, challenge: Challenge, state: State) {
+        match self.rows.iter_mut().find(|row| row.challenge == challenge) {
+            Some(row) => row.state = state,
+            None => {
+                self.rows.push(Row { challenge, state });
+                self.rows.sort_by_key(|row| row.challenge);
+            },
+        }
+    }
+
+    fn draw(&mut self) {
+        if !self.live {
+            return;
+        }
+
+        let mut stdout = io::stdout().lock();
+        if self.drawn > 0 {
+            let _ = write!(stdout, "\x1b[{}A", self.drawn);
+        }
+        for row in &self.rows {
+            let _ = writeln!(stdout, "\x1b[2K\t{}", row);
+        }
+        let _ = stdout.flush();
+
+        self.drawn = self.rows.len();
+    }
+
+    fn finish(mut self) -> Vec<Solution> {
+        self.draw();
+
+        let solutions = self
+            .rows
+            .into_iter()
+            .filter_map(|row| match row.state {
+                State::Done(solution, _) => Some(solution),
+                _ => None,
+            })
+            .collect_vec();
+
+        if !self.live {
+            for solution in &solutions {
+                println!("\t{}", solution);
+            }
+        }
+
+        solutions
+    }
+}
+
+impl std::fmt::Display for Row {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let challenge = self.challenge.to_string();
+        match &self.state {
+            State::Pending => write!(f, "{:<18} pending", challenge),
+            State::Running(started) => write!(
+                f,
+                "{:<18} running {:>10}",
+                challenge,
+                format!("{:.2?}", started.elapsed())
+            ),
+            State::Done(solution, elapsed) => {
+                let elapsed = format!("{:.2?}", elapsed);
+                match &solution.solution {
+                    Ok(answer) => {
+                        write!(f, "{:<18} done    {:>10}  {}", challenge, elapsed, answer)?;
+                        if let Some(verdict) = &solution.verdict {
+                            write!(f, " [{}]", verdict)?;
+                        }
+                        Ok(())
+                    },
+                    Err(err) => write!(f, "{:<18} error   {:>10}  {}", challenge, elapsed, err),
+                }
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::challenge::{Error, Timings};
+    use crate::select::ChallengePart;
+
+    #[test]
+    fn test_rows() {
+        let challenge = Challenge::new(2024, 7, ChallengePart::Second);
+        let row = |state| Row { challenge, state }.to_string();
+        let solution = |solution| {
+            State::Done(
+                Solution {
+                    challenge,
+                    solution,
+                    timings: Timings::default(),
+                    verdict: None,
+                },
+                Duration::from_millis(12),
+            )
+        };
+
+        assert_eq!(row(State::Pending), "2024 Day 7 Part 2  pending");
+        assert_eq!(
+            row(solution(Ok(42.into()))),
+            "2024 Day 7 Part 2  done       12.00ms  42"
+        );
+        assert_eq!(
+            row(solution(Err(Error::CancelledError()))),
+            "2024 Day 7 Part 2  error      12.00ms  cancelled"
+        );
+    }
+}
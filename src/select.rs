@@ -1,10 +1,10 @@
 use clap::ValueEnum;
-use itertools::Itertools;
 use std::fmt;
 use std::str::FromStr;
-use std::vec::IntoIter;
 use winnow::prelude::*;
 
+use crate::calendar;
+use crate::challenge::registry;
 use crate::error::Error;
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, ValueEnum)]
@@ -47,59 +47,72 @@ impl fmt::Display for ChallengePart {
 
 #[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
 pub struct Challenge {
+    pub year: usize,
     pub day: usize,
     pub part: ChallengePart,
 }
 
 impl Challenge {
-    pub fn new(day: usize, part: ChallengePart) -> Self {
-        Self { day, part }
+    pub fn new(year: usize, day: usize, part: ChallengePart) -> Self {
+        Self { year, day, part }
     }
 
-    pub fn parse(input: &mut &str) -> PResult<Self> {
-        use winnow::ascii::dec_uint;
-        use winnow::combinator::separated_pair;
-
-        separated_pair(dec_uint, 'p', ChallengePart::parse)
-            .map(|(day, part)| Self::new(day, part))
-            .parse_next(input)
+    /// Parse `[year:]<day>p<part>`, taking `default_year` when the year is left out
+    pub fn parse_in(default_year: usize) -> impl FnMut(&mut &str) -> PResult<Self> {
+        move |input: &mut &str| {
+            use winnow::ascii::dec_uint;
+            use winnow::combinator::{opt, separated_pair, terminated};
+
+            (
+                opt(terminated(parse_year, ':')),
+                separated_pair(dec_uint, 'p', ChallengePart::parse),
+            )
+                .map(|(year, (day, part))| Self::new(year.unwrap_or(default_year), day, part))
+                .parse_next(input)
+        }
     }
 
-    pub fn from_selectors(selectors: Vec<ChallengeSelector>) -> Vec<Challenge> {
-        selectors
-            .into_iter()
-            .flat_map(<ChallengeSelector as Into<Vec<Challenge>>>::into)
-            .collect()
+    /// `<year>:<day>p<part>`, as files kept between runs record challenges for
+    /// [`Challenge::from_str`] to read back
+    pub fn to_entry(self) -> String {
+        format!("{}:{}p{}", self.year, self.day, self.part as i32)
     }
 }
 
-impl From<ChallengeSelector> for Vec<Challenge> {
-    fn from(value: ChallengeSelector) -> Self {
-        use ChallengePart::*;
+/// Parses challenges recorded in files, where a missing year means the files predate years being
+/// recorded and so the challenge is from [`registry::DEFAULT_YEAR`]
+impl FromStr for Challenge {
+    type Err = Error;
 
-        match value.part {
-            None => vec![
-                Challenge::new(value.day, First),
-                Challenge::new(value.day, Second),
-            ],
-            Some(part) => vec![Challenge::new(value.day, part)],
-        }
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse_in(registry::DEFAULT_YEAR)
+            .parse(s)
+            .map_err(|_| Error::InvalidSelectionError(s.to_string()))
     }
 }
 
 impl fmt::Display for Challenge {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "Day {} {}", self.day, self.part)
+        write!(f, "{} Day {} {}", self.year, self.day, self.part)
     }
 }
 
 impl fmt::Debug for Challenge {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{}p{}", self.day, self.part as i32)
+        write!(f, "{}:{}p{}", self.year, self.day, self.part as i32)
     }
 }
 
-#[derive(Copy, Clone, PartialOrd, Ord, PartialEq, Eq)]
+/// A four digit event year, which keeps it from being mistaken for a day
+fn parse_year(input: &mut &str) -> PResult<usize> {
+    use winnow::ascii::dec_uint;
+
+    dec_uint
+        .verify(|year: &usize| (1000..10000).contains(year))
+        .parse_next(input)
+}
+
+#[derive(Debug, Copy, Clone, PartialOrd, Ord, PartialEq, Eq)]
 pub struct ChallengeSelector {
     pub day: usize,
     pub part: Option<ChallengePart>,
@@ -119,49 +132,54 @@ impl ChallengeSelector {
 
     pub fn parse(input: &mut &str) -> PResult<Self> {
         use winnow::ascii::dec_uint;
-        use winnow::combinator::alt;
+        use winnow::combinator::{alt, separated_pair};
 
         alt((
-            Challenge::parse.map(<Self as From<Challenge>>::from),
+            separated_pair(dec_uint, 'p', ChallengePart::parse)
+                .map(|(day, part)| Self::new_with_part(day, part)),
             dec_uint.map(<Self as From<usize>>::from),
         ))
         .parse_next(input)
     }
 
-    pub fn parse_list(input: &mut &str) -> PResult<Vec<Self>> {
-        use winnow::combinator::{alt, separated};
-
-        let elem = alt((
-            Self::parse_range,
-            Self::parse.map(|selected| vec![selected]),
-        ));
+    /// The challenges selected in `year`
+    fn challenges(self, year: usize) -> Vec<Challenge> {
+        use ChallengePart::*;
 
-        separated(1.., elem, ',')
-            .map(|groups: Vec<Vec<Self>>| groups.into_iter().flatten().collect())
-            .parse_next(input)
+        match self.part {
+            None => vec![
+                Challenge::new(year, self.day, First),
+                Challenge::new(year, self.day, Second),
+            ],
+            Some(part) => vec![Challenge::new(year, self.day, part)],
+        }
     }
 
-    fn parse_range(input: &mut &str) -> PResult<Vec<Self>> {
-        use winnow::combinator::separated_pair;
+    /// Every part from `start` to `end`. Days without a solver are kept, so they're reported as
+    /// missing just as when they're selected alone.
+    fn expand_range(start: Self, end: Self) -> Vec<Self> {
+        if start == end {
+            vec![start]
+        } else if start.day == end.day {
+            vec![start, end]
+        } else {
+            [
+                Self::expand_range_start(start),
+                Self::expand_range_middle(start, end),
+                Self::expand_range_end(end),
+            ]
+            .into_iter()
+            .flatten()
+            .collect()
+        }
+    }
 
-        separated_pair(Self::parse, '-', Self::parse)
-            .map(|(start, end)| {
-                if start == end {
-                    vec![start]
-                } else if start.day == end.day {
-                    vec![start, end]
-                } else {
-                    [
-                        Self::expand_range_start(start),
-                        Self::expand_range_middle(start, end),
-                        Self::expand_range_end(end),
-                    ]
-                    .into_iter()
-                    .flatten()
-                    .collect()
-                }
-            })
-            .parse_next(input)
+    fn registered(year: usize) -> Vec<ChallengeSelector> {
+        registry::registrations()
+            .into_iter()
+            .filter(|registration| registration.year == year)
+            .map(|registration| Self::new(registration.day))
+            .collect()
     }
 
     fn expand_range_start(start: ChallengeSelector) -> Vec<ChallengeSelector> {
@@ -195,15 +213,6 @@ impl ChallengeSelector {
     }
 }
 
-impl From<Challenge> for ChallengeSelector {
-    fn from(value: Challenge) -> Self {
-        Self {
-            day: value.day,
-            part: Some(value.part),
-        }
-    }
-}
-
 impl From<usize> for ChallengeSelector {
     fn from(value: usize) -> Self {
         Self {
@@ -221,33 +230,175 @@ impl FromStr for ChallengeSelector {
     }
 }
 
+/// One comma separated element of a selection, before the default year is known
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum Selection {
+    All,
+    Range(ChallengeSelector, ChallengeSelector),
+    One(ChallengeSelector),
+}
+
+impl Selection {
+    fn parse(input: &mut &str) -> PResult<Self> {
+        use winnow::combinator::{alt, separated_pair};
+
+        alt((
+            "all".value(Self::All),
+            separated_pair(ChallengeSelector::parse, '-', ChallengeSelector::parse)
+                .map(|(start, end)| Self::Range(start, end)),
+            ChallengeSelector::parse.map(Self::One),
+        ))
+        .parse_next(input)
+    }
+
+    fn challenges(self, year: usize) -> Vec<Challenge> {
+        let selectors = match self {
+            Self::All => ChallengeSelector::registered(year),
+            Self::Range(start, end) => ChallengeSelector::expand_range(start, end),
+            Self::One(selector) => vec![selector],
+        };
+
+        selectors
+            .into_iter()
+            .flat_map(|selector| selector.challenges(year))
+            .collect()
+    }
+}
+
+/// A selection for `year`, or the default year when `None`
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+struct YearSelection {
+    year: Option<usize>,
+    selection: Selection,
+}
+
+impl YearSelection {
+    fn parse(input: &mut &str) -> PResult<Self> {
+        use winnow::combinator::{alt, opt, terminated};
+
+        alt((
+            Self::parse_today,
+            (opt(terminated(parse_year, ':')), Selection::parse)
+                .map(|(year, selection)| Self { year, selection }),
+        ))
+        .parse_next(input)
+    }
+
+    /// Today's puzzle, while an Advent of Code is running
+    fn parse_today(input: &mut &str) -> PResult<Self> {
+        "today"
+            .verify_map(|_| Self::today(chrono::Utc::now()))
+            .parse_next(input)
+    }
+
+    fn today(now: chrono::DateTime<chrono::Utc>) -> Option<Self> {
+        calendar::advent_day(now).map(|(year, day)| Self {
+            year: Some(year),
+            selection: Selection::One(ChallengeSelector::new(day)),
+        })
+    }
+}
+
+/// Challenges selected on the command line, e.g. `1-3,5p1,2023:all`. Selections without a year
+/// are resolved against the default year with [`MultiChallengeSelector::challenges`].
 #[derive(Debug, Clone)]
 pub struct MultiChallengeSelector {
-    selection: Vec<Challenge>,
+    selections: Vec<YearSelection>,
 }
 
-impl IntoIterator for MultiChallengeSelector {
-    type Item = Challenge;
-    type IntoIter = IntoIter<Challenge>;
+impl MultiChallengeSelector {
+    pub fn challenges(&self, default_year: usize) -> Vec<Challenge> {
+        self.selections
+            .iter()
+            .flat_map(|selection| {
+                selection
+                    .selection
+                    .challenges(selection.year.unwrap_or(default_year))
+            })
+            .collect()
+    }
 
-    fn into_iter(self) -> Self::IntoIter {
-        self.selection.into_iter()
+    /// Every challenge in `selectors`, in the order selected
+    pub fn select_all(selectors: &[Self], default_year: usize) -> Vec<Challenge> {
+        selectors
+            .iter()
+            .flat_map(|selector| selector.challenges(default_year))
+            .collect()
     }
 }
 
 impl FromStr for MultiChallengeSelector {
     type Err = Error;
 
-    fn from_str(mut s: &str) -> Result<Self, Self::Err> {
-        ChallengeSelector::parse_list(&mut s)
-            .map(|selectors| {
-                let selection = selectors
-                    .into_iter()
-                    .flat_map(<Vec<Challenge> as From<ChallengeSelector>>::from)
-                    .collect_vec();
-
-                Self { selection }
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        use winnow::combinator::separated;
+
+        separated(1.., YearSelection::parse, ',')
+            .parse(s)
+            .map(|selections| Self { selections })
+            .map_err(|_| {
+                if s.split(',').any(|selector| selector == "today") {
+                    Error::InvalidSelectionError("there is no puzzle today".to_string())
+                } else {
+                    Error::InvalidSelectionError(s.to_string())
+                }
             })
-            .map_err(|_| Error::InvalidSelectionError(s.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ChallengePart::*;
+
+    fn select(s: &str, default_year: usize) -> Vec<Challenge> {
+        s.parse::<MultiChallengeSelector>()
+            .unwrap()
+            .challenges(default_year)
+    }
+
+    #[test]
+    fn test_select() {
+        assert_eq!(
+            select("5p1,2023:6", 2024),
+            vec![
+                Challenge::new(2024, 5, First),
+                Challenge::new(2023, 6, First),
+                Challenge::new(2023, 6, Second),
+            ]
+        );
+        assert_eq!(
+            select("2p2-3p1", 2024),
+            vec![Challenge::new(2024, 2, Second), Challenge::new(2024, 3, First)]
+        );
+        // Ranges keep days with no solver for the year, to be reported like a single day
+        assert_eq!(select("1-3", 2015).len(), 6);
+        assert!(select("all", 2015).is_empty());
+        assert!("2023:".parse::<MultiChallengeSelector>().is_err());
+        assert!("23:5p1".parse::<MultiChallengeSelector>().is_err());
+    }
+
+    #[test]
+    fn test_parse_challenge() {
+        let mut parse = Challenge::parse_in(2024);
+        assert_eq!(
+            parse.parse("2023:5p1").unwrap(),
+            Challenge::new(2023, 5, First)
+        );
+        assert_eq!(
+            parse.parse("12p2").unwrap(),
+            Challenge::new(2024, 12, Second)
+        );
+        assert_eq!(format!("{:?}", Challenge::new(2023, 5, First)), "2023:5p1");
+    }
+
+    #[test]
+    fn test_today() {
+        let now = "2023-12-07T12:00:00Z".parse().unwrap();
+        let today = YearSelection::today(now).unwrap();
+        assert_eq!(today.year, Some(2023));
+        assert_eq!(today.selection, Selection::One(ChallengeSelector::new(7)));
+
+        assert!(YearSelection::today("2024-06-01T00:00:00Z".parse().unwrap()).is_none());
     }
 }
@@ -0,0 +1,168 @@
+use std::fmt;
+use std::process::ExitCode;
+
+use crate::answers::Verdict;
+use crate::challenge::{self, Solution};
+
+pub const EXIT_CODES: &str = "\
+Exit codes:
+  0  every challenge succeeded
+  1  an answer was refused or rejected, or another failure
+  2  invalid arguments
+  3  configuration or input error
+  4  a solver returned an error or timed out
+  5  a solver panicked
+  6  an answer didn't match its accepted answer";
+
+/// Outcome of a whole run, from least to most severe
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Status {
+    Success,
+    Failure,
+    Mismatch,
+    SolverError,
+    Panic,
+    InputError,
+}
+
+impl Status {
+    pub fn code(self) -> u8 {
+        match self {
+            Self::Success => 0,
+            Self::Failure => 1,
+            Self::InputError => 3,
+            Self::SolverError => 4,
+            Self::Panic => 5,
+            Self::Mismatch => 6,
+        }
+    }
+
+    pub fn of_error(err: &challenge::Error) -> Self {
+        use challenge::Error::*;
+
+        match err {
+            InvalidDay(_) | DayNotImplemented(..) | InputError(_) => Self::InputError,
+            SolverPanicError(_) => Self::Panic,
+            _ => Self::SolverError,
+        }
+    }
+
+    pub fn of_solution(solution: &Solution) -> Self {
+        match (&solution.solution, &solution.verdict) {
+            (Err(err), _) => Self::of_error(err),
+            (Ok(_), Some(Verdict::Wrong(_))) => Self::Mismatch,
+            (Ok(_), _) => Self::Success,
+        }
+    }
+
+    /// The most severe status of any solution
+    pub fn of_solutions(solutions: &[Solution]) -> Self {
+        solutions
+            .iter()
+            .map(Self::of_solution)
+            .max()
+            .unwrap_or(Self::Success)
+    }
+}
+
+impl From<Status> for ExitCode {
+    fn from(status: Status) -> Self {
+        ExitCode::from(status.code())
+    }
+}
+
+/// How many challenges in a run passed, and how the rest failed
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub struct Summary {
+    pub passed: usize,
+    pub wrong: usize,
+    pub failed: usize,
+    pub panicked: usize,
+    pub timed_out: usize,
+}
+
+impl Summary {
+    pub fn new(solutions: &[Solution]) -> Self {
+        solutions
+            .iter()
+            .fold(Self::default(), |mut summary, solution| {
+                use challenge::Error::*;
+
+                match (&solution.solution, &solution.verdict) {
+                    (Ok(_), Some(Verdict::Wrong(_))) => summary.wrong += 1,
+                    (Ok(_), _) => summary.passed += 1,
+                    (Err(SolverPanicError(_)), _) => summary.panicked += 1,
+                    (Err(TimeoutError(_) | CancelledError()), _) => summary.timed_out += 1,
+                    (Err(_), _) => summary.failed += 1,
+                }
+
+                summary
+            })
+    }
+}
+
+impl fmt::Display for Summary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} passed, {} wrong, {} failed, {} panicked, {} timed out",
+            self.passed, self.wrong, self.failed, self.panicked, self.timed_out
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+    use crate::challenge::{Error, Timings};
+    use crate::select::{Challenge, ChallengePart};
+
+    fn solution(
+        solution: challenge::Result<challenge::Answer>,
+        verdict: Option<Verdict>,
+    ) -> Solution {
+        Solution {
+            challenge: Challenge::new(2024, 1, ChallengePart::First),
+            solution,
+            timings: Timings::default(),
+            verdict,
+        }
+    }
+
+    #[test]
+    fn test_status() {
+        let mut solutions = vec![
+            solution(Ok(1.into()), Some(Verdict::Correct)),
+            solution(Ok(2.into()), None),
+        ];
+        assert_eq!(Status::of_solutions(&solutions), Status::Success);
+
+        solutions.push(solution(Ok(3.into()), Some(Verdict::Wrong(4.into()))));
+        assert_eq!(Status::of_solutions(&solutions), Status::Mismatch);
+
+        solutions.push(solution(
+            Err(Error::TimeoutError(Duration::from_secs(1))),
+            None,
+        ));
+        assert_eq!(Status::of_solutions(&solutions), Status::SolverError);
+
+        solutions.push(solution(Err(Error::SolverPanicError("oops".into())), None));
+        assert_eq!(Status::of_solutions(&solutions), Status::Panic);
+
+        solutions.push(solution(Err(Error::DayNotImplemented(2024, 24)), None));
+        assert_eq!(Status::of_solutions(&solutions), Status::InputError);
+
+        assert_eq!(
+            Summary::new(&solutions),
+            Summary {
+                passed: 2,
+                wrong: 1,
+                failed: 1,
+                panicked: 1,
+                timed_out: 1,
+            }
+        );
+    }
+}
@@ -0,0 +1,34 @@
+//! Machine-readable output, as written by the binary for a real run
+
+use std::process::Command;
+
+const DAY5_EXAMPLE: &str = "47|53\n97|13\n97|61\n97|47\n75|29\n61|13\n75|53\n29|13\n97|29\n\
+                            53|29\n61|53\n97|53\n61|29\n47|13\n75|47\n97|75\n47|61\n75|61\n\
+                            47|29\n75|13\n53|13\n\n75,47,61,53,29\n97,61,53,29,13\n75,29,13\n\
+                            75,97,47,61,53\n61,13,29\n97,13,75,29,47\n";
+
+#[test]
+fn test_json_output() {
+    let dir = std::env::temp_dir().join(format!("aoc24-json-output-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let input = dir.join("day5");
+    std::fs::write(&input, DAY5_EXAMPLE).unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_aoc24"))
+        .args(["-y", "2024", "-o", "json", "--input-dir"])
+        .arg(&dir)
+        .arg("--input")
+        .arg(&input)
+        .arg("5")
+        .output()
+        .unwrap();
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let json: serde_json::Value = serde_json::from_str(&stdout)
+        .unwrap_or_else(|err| panic!("stdout isn't only JSON ({}):\n{}", err, stdout));
+
+    assert_eq!(json[0]["day"], 5);
+    assert_eq!(json[0]["answer"], 143);
+    assert_eq!(json[1]["answer"], 123);
+}